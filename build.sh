@@ -25,7 +25,7 @@ for arg in "$@"; do
 done
 
 # Build normal crates normally
-for crate in ast cli interpreter parser; do
+for crate in ast cli diagnostics interpreter parser; do
   cd $START_DIR/crates/$crate
   cargo build $CARGO_FLAG
   if $DO_TEST; then
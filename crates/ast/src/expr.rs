@@ -2,17 +2,78 @@ use std::collections::HashMap;
 
 use is_macro::Is;
 
+use crate::{Span, Stmt};
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
 #[derive(Debug, Is, Clone)]
-pub enum Expr {
+pub enum ExprKind {
     Ident(String),
     NumberLiteral(f64),
     StringLiteral(String),
+    /// A string literal with embedded expressions, like `"Hello ${name}"`
+    Template(Vec<TemplatePart>),
     BoolLiteral(bool),
+    NullLiteral,
     ArrayLiteral(Vec<Expr>),
     ObjectLiteral(HashMap<String, Expr>),
+    UnaryOp(UnaryOp),
     BinaryOp(BinaryOp),
     FnCall(FnCall),
+    /// `receiver.method(args)`
+    MethodCall(MethodCall),
+    /// Both `parent[child]` and `parent.child`, which is stored with a string literal child.
     Member(Member),
+    /// `...iterable`, which is only allowed as an array item or a function argument
+    Spread(Box<Expr>),
+    /// `start..end`, which includes `start` but not `end`
+    Range(Range),
+    /// An anonymous function, like `fn(x) { return x * 2; }` or `fn(x) => x * 2`
+    Function(Function),
+}
+
+#[derive(Debug, Clone)]
+pub struct Range {
+    pub start: Box<Expr>,
+    pub end: Box<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub prop_idents: Vec<String>,
+    pub body: Vec<Stmt>,
+    /// Written as `fn*`, so calling it returns an iterator over what its body yields.
+    pub is_generator: bool,
+}
+
+#[derive(Debug, Is, Clone)]
+pub enum TemplatePart {
+    Literal(String),
+    Expr(Expr),
+}
+
+#[derive(Debug, Clone)]
+pub struct UnaryOp {
+    pub kind: UnaryOpKind,
+    pub operand: Box<Expr>,
+}
+
+#[derive(Debug, Is, Clone, Copy)]
+pub enum UnaryOpKind {
+    /// `-a`
+    Negate,
+    /// `!a`
+    Not,
 }
 
 #[derive(Debug, Clone)]
@@ -28,10 +89,74 @@ pub enum BinaryOpKind {
     Subtract,
     Multiply,
     Divide,
+    /// `a ~/ b`, which divides and rounds towards negative infinity
+    IntegerDivide,
+    /// `a % b`, which takes the sign of `a`
+    Remainder,
     GreaterThan,
+    GreaterThanOrEquals,
     LessThan,
+    LessThanOrEquals,
     Equals,
+    NotEquals,
     Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    /// `&&`, which only evaluates its right side if the left is `true`
+    And,
+    /// `||`, which only evaluates its right side if the left is `false`
+    Or,
+}
+
+impl BinaryOpKind {
+    /// How tightly the operator binds to its operands.
+    /// Operators with a higher precedence are grouped first.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOpKind::Or => 1,
+            BinaryOpKind::And => 2,
+            BinaryOpKind::GreaterThan
+            | BinaryOpKind::GreaterThanOrEquals
+            | BinaryOpKind::LessThan
+            | BinaryOpKind::LessThanOrEquals
+            | BinaryOpKind::Equals
+            | BinaryOpKind::NotEquals => 3,
+            BinaryOpKind::BitOr => 4,
+            BinaryOpKind::BitXor => 5,
+            BinaryOpKind::BitAnd => 6,
+            BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight => 7,
+            BinaryOpKind::Add | BinaryOpKind::Subtract => 8,
+            BinaryOpKind::Multiply
+            | BinaryOpKind::Divide
+            | BinaryOpKind::IntegerDivide
+            | BinaryOpKind::Remainder => 9,
+            BinaryOpKind::Pow => 10,
+        }
+    }
+
+    /// Whether the right side is only evaluated depending on the value of the left.
+    pub fn is_short_circuiting(&self) -> bool {
+        matches!(self, BinaryOpKind::And | BinaryOpKind::Or)
+    }
+
+    /// How a chain of operators with the same precedence is grouped.
+    pub fn associativity(&self) -> Associativity {
+        match self {
+            BinaryOpKind::Pow => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+}
+
+#[derive(Debug, Is, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is grouped as `(a - b) - c`
+    Left,
+    /// `a ** b ** c` is grouped as `a ** (b ** c)`
+    Right,
 }
 
 #[derive(Debug, Is, Clone, Copy)]
@@ -42,7 +167,14 @@ pub enum AssignOpKind {
 
 #[derive(Debug, Clone)]
 pub struct FnCall {
-    pub ident: String,
+    pub callee: Box<Expr>,
+    pub args: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodCall {
+    pub receiver: Box<Expr>,
+    pub method: String,
     pub args: Vec<Expr>,
 }
 
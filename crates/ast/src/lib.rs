@@ -1,7 +1,9 @@
 mod expr;
+mod span;
 mod stmt;
 
 pub use expr::*;
+pub use span::{LineCol, Span};
 pub use stmt::*;
 
 pub type Program = Vec<Stmt>;
@@ -1,16 +1,35 @@
 use is_macro::Is;
 
-use crate::{AssignOpKind, Expr};
+use crate::{AssignOpKind, Expr, Span};
+
+#[derive(Debug, Clone)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
 
 #[derive(Debug, Is, Clone)]
-pub enum Stmt {
+pub enum StmtKind {
     VarDecl(VarDecl),
     VarAssign(VarAssign),
     FnDecl(FnDecl),
     WhileLoop(WhileLoop),
+    ForLoop(ForLoop),
     BlockExit(BlockExit),
     IfElse(IfElse),
+    /// `yield value;`, which is only allowed in the body of a generator function
+    // `yield` is reserved in Rust, so it can't be the name of the generated methods.
+    #[is(name = "yield_stmt")]
+    Yield(Expr),
     Expr(Expr),
+    /// A statement that could not be parsed, left in place of it when the parser recovers from errors
+    Error,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +50,8 @@ pub struct FnDecl {
     pub ident: String,
     pub prop_idents: Vec<String>,
     pub body: Vec<Stmt>,
+    /// Declared with `fn*`, so calling it returns an iterator over what its body yields.
+    pub is_generator: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +60,18 @@ pub struct WhileLoop {
     pub body: Vec<Stmt>,
 }
 
+/// `for (first in iterable)` or `for (first, second in iterable)`
+///
+/// With one identifier, it is bound to each item, or each key when iterating over an object.
+/// With two, the first is bound to the index or key and the second to the item.
+#[derive(Debug, Clone)]
+pub struct ForLoop {
+    pub first: String,
+    pub second: Option<String>,
+    pub iterable: Expr,
+    pub body: Vec<Stmt>,
+}
+
 #[derive(Debug, Clone)]
 pub enum BlockExit {
     FnReturn(Option<Expr>),
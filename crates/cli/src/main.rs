@@ -1,25 +1,51 @@
 use std::fs::read;
-use std::io::stderr;
-use std::path::PathBuf;
+use std::io::{stderr, IsTerminal};
+use std::path::{Path, PathBuf};
 
 use ast::Program;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use crossterm::execute;
 use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use diagnostics::{Code, Diagnostic, JsonRenderer, Renderer, TerminalRenderer};
 use interpreter::{BlockExit, Context, NativeFn, Value};
-use parser::{lex_string, parse_tokens};
+use parser::{lex_string, parse_tokens_recovering, ParseError};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[command(subcommand)]
     subcommand: Action,
+    /// How errors are shown
+    #[arg(long, value_enum, global = true, default_value_t = Format::Terminal)]
+    format: Format,
 }
 
 #[derive(Debug, Subcommand)]
 enum Action {
-    Run { filename: PathBuf },
-    Ast { filename: PathBuf },
+    Run {
+        filename: PathBuf,
+    },
+    Ast {
+        filename: PathBuf,
+    },
+    /// Report every syntax error in a file without running it
+    Check {
+        filename: PathBuf,
+    },
+    /// Describe an error code, like `E0204`, in detail
+    Explain {
+        code: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The source code with the problem underlined, coloured when writing to a terminal
+    Terminal,
+    /// The same as `terminal`, but never coloured
+    Plain,
+    /// A JSON array of diagnostics, written to stdout
+    Json,
 }
 
 fn main() {
@@ -27,17 +53,25 @@ fn main() {
 
     match args.subcommand {
         Action::Run { filename } => {
-            let Some(ast) = load_ast(&filename) else {
-                print_err("Could not load AST");
+            let Some(source) = load_source(&filename) else {
                 return;
             };
 
+            let ast = match process_ast(&source) {
+                Ok(ast) => ast,
+                Err(diagnostics) => {
+                    report(&diagnostics, &source, &filename, args.format);
+                    print_err("Could not load AST");
+                    return;
+                }
+            };
+
             let mut context = Context::new();
             context.add_stdlib();
             add_io(&mut context);
 
-            match context.eval_program(&ast) {
-                Err(err) => println!("{:#?}", err),
+            match context.run_program(&ast) {
+                Err(err) => report(&[err.to_diagnostic()], &source, &filename, args.format),
                 Ok(BlockExit::Returned(Some(_v))) => {
                     println!("{}", _v);
                 }
@@ -45,16 +79,42 @@ fn main() {
             }
         }
         Action::Ast { filename } => {
-            let Some(ast) = load_ast(&filename) else {
-                print_err("Could not load AST");
+            let Some(source) = load_source(&filename) else {
                 return;
             };
-            eprintln!("{:#?}", ast);
+
+            match process_ast(&source) {
+                Ok(ast) => eprintln!("{:#?}", ast),
+                Err(diagnostics) => {
+                    report(&diagnostics, &source, &filename, args.format);
+                    print_err("Could not load AST");
+                }
+            }
+        }
+        Action::Check { filename } => {
+            let Some(source) = load_source(&filename) else {
+                std::process::exit(1);
+            };
+
+            let diagnostics = process_ast(&source).err().unwrap_or_default();
+            report(&diagnostics, &source, &filename, args.format);
+
+            if !diagnostics.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Action::Explain { code } => {
+            let Some(code) = Code::find(&code) else {
+                print_err(&format!("`{}` is not an error code.", code));
+                std::process::exit(1);
+            };
+
+            print!("{}", code.explanation());
         }
     }
 }
 
-fn load_ast(filename: &PathBuf) -> Option<Program> {
+fn load_source(filename: &PathBuf) -> Option<String> {
     let Ok(file) = read(filename) else {
         print_err("Could not load file from disk.");
         return None;
@@ -65,113 +125,44 @@ fn load_ast(filename: &PathBuf) -> Option<Program> {
         return None;
     };
 
-    process_ast(&source)
+    Some(source)
 }
 
-fn process_ast(source: &str) -> Option<Program> {
-    let tokens = match lex_string(source) {
-        Ok(tokens) => tokens,
-        Err(err) => {
-            let (line, col) = line_col_from_index(err.index, source).unwrap();
-
-            println!("{:#?}", err);
-            println!("At line {}, row {}", line, col);
-            return None;
-        }
-    };
-
-    match parse_tokens(&tokens) {
-        Ok(ast) => Some(ast),
-        Err(err) => {
-            let token = &tokens[err.index];
+/// Lexes and parses the source, describing every syntax error if there are any.
+fn process_ast(source: &str) -> Result<Program, Vec<Diagnostic>> {
+    let tokens = lex_string(source).map_err(|err| vec![err.to_diagnostic()])?;
 
-            let (start_line, start_col) = line_col_from_index(token.span.start, source).unwrap();
-            let (end_line, end_col) = line_col_from_index(token.span.end, source).unwrap();
+    let (ast, errors) = parse_tokens_recovering(&tokens);
 
-            print_line_col(start_line, end_line, start_col, end_col, source);
-
-            if err.is_recoverable {
-                print!("Recoverable Error: ");
-            } else {
-                print!("Unrecoverable Error: ")
-            }
-
-            println!("{}", err.kind);
-            println!("At line {}, row {}", start_line, start_col);
-            None
-        }
+    if errors.is_empty() {
+        Ok(ast)
+    } else {
+        Err(errors.iter().map(ParseError::to_diagnostic).collect())
     }
 }
 
-/// Computes the line:column an index is at in source code.
-/// Returns `None` if the index is outside the source.
-fn line_col_from_index(index: usize, source: &str) -> Option<(usize, usize)> {
-    let mut traversed = 0;
-
-    for (nth_line, source_line) in source.lines().enumerate() {
-        let line_len = source_line.chars().count();
-
-        if (traversed..traversed + line_len).contains(&index) {
-            return Some((nth_line + 1, index - traversed + 1));
-        }
-
-        traversed += line_len + 1;
+/// Shows diagnostics about a file in the chosen format.
+fn report(diagnostics: &[Diagnostic], source: &str, filename: &Path, format: Format) {
+    if format == Format::Json {
+        println!("{}", JsonRenderer.render(diagnostics, source));
+        return;
     }
 
-    None
-}
-
-fn print_line_col(
-    start_line: usize,
-    end_line: usize,
-    start_col: usize,
-    end_col: usize,
-    source: &str,
-) {
-    let end_line_nr_str = format!("{}", start_line);
-    let padding = " ".repeat(end_line_nr_str.len());
+    let renderer = if format == Format::Terminal && stderr().is_terminal() {
+        TerminalRenderer::coloured()
+    } else {
+        TerminalRenderer::plain()
+    };
 
-    let mut stderr = stderr();
+    eprint!(
+        "{}",
+        renderer
+            .with_file_name(filename.display().to_string())
+            .render(diagnostics, source)
+    );
 
-    for (index, line) in source.lines().enumerate() {
-        if start_line > 1 && index == start_line - 2 {
-            execute!(
-                stderr,
-                SetForegroundColor(Color::Blue),
-                Print(format!("{} |\n", padding)),
-                ResetColor
-            )
-            .unwrap();
-        } else if index == end_line {
-            execute!(
-                stderr,
-                SetForegroundColor(Color::Blue),
-                Print(format!("{} | ", padding)),
-                SetForegroundColor(Color::Red),
-                Print(format!(
-                    "{}{}\n",
-                    " ".repeat(start_col - 1),
-                    "^".repeat(end_col - start_col)
-                )),
-                ResetColor
-            )
-            .unwrap();
-        } else if index >= start_line - 1 && index < end_line {
-            let nr_string = format!("{}", index + 1);
-            execute!(
-                stderr,
-                SetForegroundColor(Color::Blue),
-                Print(format!(
-                    "{}{} |",
-                    " ".repeat(padding.len() - nr_string.len()),
-                    nr_string
-                )),
-                SetForegroundColor(Color::White),
-                Print(format!(" {}\n", line)),
-                ResetColor
-            )
-            .unwrap();
-        }
+    if diagnostics.len() > 1 {
+        eprintln!("\nFound {} errors.", diagnostics.len());
     }
 }
 
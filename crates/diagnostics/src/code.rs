@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+
+/// A stable identifier for a kind of problem, like `E0204`.
+///
+/// Every code comes with a longer explanation of what causes the problem and how to fix it, which is written
+/// in `explanations/<code>.md`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Code {
+    id: &'static str,
+    title: &'static str,
+    explanation: &'static str,
+}
+
+impl Code {
+    pub fn id(self) -> &'static str {
+        self.id
+    }
+
+    /// A short summary of the problem.
+    pub fn title(self) -> &'static str {
+        self.title
+    }
+
+    /// A longer description of the problem, in markdown.
+    pub fn explanation(self) -> &'static str {
+        self.explanation
+    }
+
+    /// Looks up a code by its identifier, ignoring case.
+    pub fn find(id: &str) -> Option<Code> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|code| code.id.eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+impl Display for Code {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+macro_rules! codes {
+    ($($name:ident = $id:literal: $title:literal),* $(,)?) => {
+        impl Code {
+            $(
+                #[doc = $title]
+                pub const $name: Code = Code {
+                    id: $id,
+                    title: $title,
+                    explanation: include_str!(concat!("../explanations/", $id, ".md")),
+                };
+            )*
+
+            /// Every code, in order.
+            pub const ALL: &'static [Code] = &[$(Self::$name),*];
+        }
+    };
+}
+
+codes! {
+    // Lexing
+    UNEXPECTED_CHARACTER = "E0101": "A character that can't start any token",
+    UNTERMINATED_STRING = "E0102": "A string that is never closed",
+    UNTERMINATED_INTERPOLATION = "E0103": "An interpolated expression that is never closed",
+    INVALID_ESCAPE = "E0104": "An unknown escape sequence",
+    INVALID_UNICODE_ESCAPE = "E0105": "A malformed unicode escape sequence",
+    // Parsing
+    EXPECTED_TOKEN = "E0201": "A specific token was expected",
+    EXPECTED_EXPRESSION = "E0204": "An expression was expected",
+    FAILED_TO_CONSUME = "E0205": "Input was left over after parsing",
+    // Running
+    REDECLARATION = "E0301": "A variable was declared twice",
+    UNDECLARED = "E0302": "Assignment to a variable that was never declared",
+    TYPE_ERROR = "E0303": "A value of the wrong type was used",
+    INVALID_BINARY_OP_ARGS = "E0304": "An operator was used with values it doesn't support",
+    UNDEFINED_VARIABLE = "E0305": "A variable that doesn't exist was used",
+    INCORRECT_ARGUMENT_COUNT = "E0307": "A function was called with the wrong number of arguments",
+    EXPECTED_INTEGER = "E0308": "A fractional number was used where an integer is required",
+    SHIFT_OUT_OF_RANGE = "E0309": "A bit shift by too many places",
+    INDEX_OUT_OF_BOUNDS = "E0310": "An index past the end of an array or string",
+    ASSIGNMENT_OUT_OF_BOUNDS = "E0311": "An assignment to an index past the end of an array",
+    CANNOT_ASSIGN_INDEX = "E0312": "An assignment to an index of a value that can't be changed that way",
+    INVALID_ASSIGNMENT_TARGET = "E0313": "An assignment to something that isn't a variable, item or key",
+    OBJECT_MISSING_KEY = "E0314": "A key that an object doesn't have was used",
+    UNDEFINED_METHOD = "E0315": "A method that a value doesn't have was called",
+    YIELD_OUTSIDE_GENERATOR = "E0317": "A `yield` outside of a generator function",
+    SYNTAX_ERROR = "E0318": "A statement that failed to parse was run",
+    CANNOT_ITERATE = "E0319": "A value that can't be iterated over was looped over",
+    CANNOT_INDEX_TYPE = "E0320": "A value that can't be indexed was indexed",
+    ITERATOR_ALREADY_RUNNING = "E0321": "An iterator was advanced while it was producing an item",
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::Code;
+
+    #[test]
+    fn ids_are_unique() {
+        let ids: HashSet<_> = Code::ALL.iter().map(|code| code.id()).collect();
+
+        assert_eq!(ids.len(), Code::ALL.len());
+    }
+
+    #[test]
+    fn every_code_is_explained() {
+        for code in Code::ALL {
+            assert!(
+                code.explanation()
+                    .starts_with(&format!("# {}", code.title())),
+                "The explanation of {code} should start with its title."
+            );
+        }
+    }
+
+    #[test]
+    fn finds_codes_ignoring_case() {
+        assert_eq!(Code::find("e0204"), Some(Code::EXPECTED_EXPRESSION));
+        assert_eq!(Code::find("E9999"), None);
+    }
+}
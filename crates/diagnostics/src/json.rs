@@ -0,0 +1,153 @@
+use std::fmt::Write;
+
+use ast::LineCol;
+
+use crate::{char_boundary, Diagnostic, Label, Renderer};
+
+/// Renders diagnostics as a JSON array, for editors and other tools to read.
+///
+/// Each label has its span in bytes, along with the line and column it starts and ends at, counted from 1
+/// in characters.
+///
+/// ```json
+/// [{"severity":"error","code":"E0204","message":"Expected an expression, but found `;`",
+///   "labels":[{"style":"primary","message":"expected an expression","start":8,"end":9,
+///              "line":1,"column":9,"end_line":1,"end_column":10}],
+///   "notes":[],"help":null}]
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonRenderer;
+
+impl JsonRenderer {
+    fn render_one(diagnostic: &Diagnostic, source: &str, out: &mut String) -> std::fmt::Result {
+        write!(
+            out,
+            r#"{{"severity":{},"code":{},"message":{},"labels":["#,
+            string(&diagnostic.severity.to_string()),
+            string(diagnostic.code.id()),
+            string(&diagnostic.message)
+        )?;
+
+        for (index, label) in diagnostic.labels.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+
+            render_label(label, source, out)?;
+        }
+
+        out.push_str(r#"],"notes":["#);
+
+        for (index, note) in diagnostic.notes.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+
+            out.push_str(&string(note));
+        }
+
+        let help = diagnostic
+            .help
+            .as_deref()
+            .map_or("null".to_string(), string);
+
+        write!(out, r#"],"help":{help}}}"#)
+    }
+}
+
+fn render_label(label: &Label, source: &str, out: &mut String) -> std::fmt::Result {
+    let start = LineCol::from_index(source, char_boundary(source, label.span.start));
+    let end = LineCol::from_index(source, char_boundary(source, label.span.end));
+
+    write!(
+        out,
+        r#"{{"style":{},"message":{},"start":{},"end":{},"line":{},"column":{},"end_line":{},"end_column":{}}}"#,
+        string(&label.style.to_string()),
+        string(&label.message),
+        label.span.start,
+        label.span.end,
+        start.line,
+        start.col,
+        end.line,
+        end.col
+    )
+}
+
+/// Quotes and escapes text as a JSON string.
+fn string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, diagnostics: &[Diagnostic], source: &str) -> String {
+        let mut out = String::from("[");
+
+        for (index, diagnostic) in diagnostics.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+
+            Self::render_one(diagnostic, source, &mut out)
+                .expect("Writing to a String can't fail.");
+        }
+
+        out.push(']');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::Span;
+
+    use super::JsonRenderer;
+    use crate::{Code, Diagnostic, Label, Renderer};
+
+    #[test]
+    fn renders_every_field() {
+        let source = "let a = ;";
+        let diagnostic = Diagnostic::error(
+            Code::EXPECTED_EXPRESSION,
+            "Expected an expression, but found `;`",
+        )
+        .with_label(Label::primary(Span::new(8, 9), "expected an expression"))
+        .with_note("Every variable needs a value.");
+
+        assert_eq!(
+            JsonRenderer.render(&[diagnostic], source),
+            r#"[{"severity":"error","code":"E0204","message":"Expected an expression, but found `;`","labels":[{"style":"primary","message":"expected an expression","start":8,"end":9,"line":1,"column":9,"end_line":1,"end_column":10}],"notes":["Every variable needs a value."],"help":null}]"#
+        );
+    }
+
+    #[test]
+    fn escapes_strings() {
+        let diagnostic =
+            Diagnostic::error(Code::SYNTAX_ERROR, "A \"quoted\"\n\\ message\u{1}").with_help("é");
+
+        assert_eq!(
+            JsonRenderer.render(&[diagnostic], ""),
+            r#"[{"severity":"error","code":"E0318","message":"A \"quoted\"\n\\ message\u0001","labels":[],"notes":[],"help":"é"}]"#
+        );
+    }
+
+    #[test]
+    fn renders_nothing_as_an_empty_array() {
+        assert_eq!(JsonRenderer.render(&[], ""), "[]");
+    }
+}
@@ -0,0 +1,27 @@
+#![doc = include_str!("../README.md")]
+
+mod code;
+mod diagnostic;
+mod json;
+mod terminal;
+
+pub use code::Code;
+pub use diagnostic::{Diagnostic, Label, LabelStyle, Severity};
+pub use json::JsonRenderer;
+pub use terminal::TerminalRenderer;
+
+/// Turns diagnostics about a piece of source code into text.
+pub trait Renderer {
+    fn render(&self, diagnostics: &[Diagnostic], source: &str) -> String;
+}
+
+/// Moves a byte index back onto the start of the character it is inside of, so the source can be sliced there.
+pub(crate) fn char_boundary(source: &str, index: usize) -> usize {
+    let mut index = index.min(source.len());
+
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
@@ -0,0 +1,342 @@
+use std::fmt::Write;
+
+use ast::LineCol;
+
+use crate::{char_boundary, Diagnostic, Label, Renderer, Severity};
+
+/// Renders diagnostics for people to read, with the source code they point at underlined.
+///
+/// ```text
+/// error[E0204]: Expected an expression, but found `;`
+///  --> main.th:1:9
+///   |
+/// 1 | let a = ;
+///   |         ^ expected an expression
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TerminalRenderer {
+    colour: bool,
+    file_name: Option<String>,
+}
+
+/// The ANSI escape codes the renderer colours its output with.
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    Error,
+    Warning,
+    Note,
+    Gutter,
+    Bold,
+}
+
+impl Style {
+    fn escape_code(self) -> &'static str {
+        match self {
+            Style::Error => "\x1b[1;31m",
+            Style::Warning => "\x1b[1;33m",
+            Style::Note => "\x1b[1;36m",
+            Style::Gutter => "\x1b[1;34m",
+            Style::Bold => "\x1b[1m",
+        }
+    }
+}
+
+impl From<Severity> for Style {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Error => Style::Error,
+            Severity::Warning => Style::Warning,
+            Severity::Note => Style::Note,
+        }
+    }
+}
+
+/// Where a label is in the source, counting lines and columns from 1.
+struct LabelPosition {
+    start: LineCol,
+    end: LineCol,
+}
+
+impl LabelPosition {
+    fn new(label: &Label, source: &str) -> Self {
+        Self {
+            start: LineCol::from_index(source, char_boundary(source, label.span.start)),
+            end: LineCol::from_index(source, char_boundary(source, label.span.end)),
+        }
+    }
+}
+
+impl TerminalRenderer {
+    /// A renderer that colours its output with ANSI escape codes.
+    pub fn coloured() -> Self {
+        Self {
+            colour: true,
+            file_name: None,
+        }
+    }
+
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    /// Names the file the source code came from in the rendered output.
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    fn paint(&self, text: &str, style: Style) -> String {
+        if self.colour {
+            format!("{}{text}\x1b[0m", style.escape_code())
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn render_one(
+        &self,
+        diagnostic: &Diagnostic,
+        source: &str,
+        out: &mut String,
+    ) -> std::fmt::Result {
+        let severity_style = Style::from(diagnostic.severity);
+
+        writeln!(
+            out,
+            "{}{}",
+            self.paint(
+                &format!("{}[{}]", diagnostic.severity, diagnostic.code),
+                severity_style
+            ),
+            self.paint(&format!(": {}", diagnostic.message), Style::Bold)
+        )?;
+
+        let positions: Vec<_> = diagnostic
+            .labels
+            .iter()
+            .map(|label| LabelPosition::new(label, source))
+            .collect();
+
+        let gutter_width = positions
+            .iter()
+            .map(|position| position.start.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let padding = " ".repeat(gutter_width);
+        let empty_gutter = self.paint(&format!("{padding} |"), Style::Gutter);
+
+        let location = diagnostic
+            .primary_span()
+            .map(|span| LineCol::from_index(source, char_boundary(source, span.start)))
+            .or_else(|| positions.first().map(|position| position.start));
+
+        if let Some(location) = location {
+            let file = self
+                .file_name
+                .as_ref()
+                .map_or(String::new(), |name| format!("{name}:"));
+
+            writeln!(
+                out,
+                "{padding}{} {file}{}:{}",
+                self.paint("-->", Style::Gutter),
+                location.line,
+                location.col
+            )?;
+            writeln!(out, "{empty_gutter}")?;
+        }
+
+        let mut labels: Vec<_> = diagnostic.labels.iter().zip(&positions).collect();
+        // Stable, so labels on the same line stay in the order they were added.
+        labels.sort_by_key(|(_, position)| position.start.line);
+
+        let mut previous_line = None;
+
+        for (label, position) in labels {
+            let line = position.start.line;
+            let text = source.lines().nth(line - 1).unwrap_or_default();
+
+            // Labels on the same line share one copy of it.
+            if previous_line != Some(line) {
+                if previous_line.is_some_and(|previous| line > previous + 1) {
+                    writeln!(out, "{}", self.paint("...", Style::Gutter))?;
+                }
+
+                writeln!(
+                    out,
+                    "{} {text}",
+                    self.paint(&format!("{line:>gutter_width$} |"), Style::Gutter)
+                )?;
+
+                previous_line = Some(line);
+            }
+
+            // Labels over several lines are only underlined on their first line, up to where it ends.
+            let underline_to = if position.end.line == line {
+                position.end.col
+            } else {
+                text.trim_end().chars().count() + 1
+            };
+
+            let (marker, style) = if label.style.is_primary() {
+                ("^", severity_style)
+            } else {
+                ("-", Style::Gutter)
+            };
+
+            let mut underline =
+                marker.repeat(underline_to.saturating_sub(position.start.col).max(1));
+
+            if !label.message.is_empty() {
+                underline.push(' ');
+                underline.push_str(&label.message);
+            }
+
+            writeln!(
+                out,
+                "{empty_gutter} {}{}",
+                " ".repeat(position.start.col - 1),
+                self.paint(&underline, style)
+            )?;
+        }
+
+        for note in &diagnostic.notes {
+            writeln!(
+                out,
+                "{padding} {} {note}",
+                self.paint("= note:", Style::Bold)
+            )?;
+        }
+
+        if let Some(help) = &diagnostic.help {
+            writeln!(
+                out,
+                "{padding} {} {help}",
+                self.paint("= help:", Style::Bold)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn render(&self, diagnostics: &[Diagnostic], source: &str) -> String {
+        let mut out = String::new();
+
+        for (index, diagnostic) in diagnostics.iter().enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+
+            self.render_one(diagnostic, source, &mut out)
+                .expect("Writing to a String can't fail.");
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::Span;
+
+    use super::TerminalRenderer;
+    use crate::{Code, Diagnostic, Label, Renderer};
+
+    #[test]
+    fn underlines_labels() {
+        let source = "fn get(arr) {\n  return arr[5];\n}\nget([]);";
+        let diagnostic = Diagnostic::error(Code::INDEX_OUT_OF_BOUNDS, "Index 5 is out of bounds.")
+            .with_label(Label::primary(Span::new(23, 29), "this index"))
+            .with_label(Label::secondary(Span::new(33, 40), "`get` was called here"))
+            .with_note("The array is empty.");
+
+        let rendered = TerminalRenderer::plain()
+            .with_file_name("main.th")
+            .render(&[diagnostic], source);
+
+        assert_eq!(
+            rendered,
+            "error[E0310]: Index 5 is out of bounds.
+ --> main.th:2:10
+  |
+2 |   return arr[5];
+  |          ^^^^^^ this index
+...
+4 | get([]);
+  | ------- `get` was called here
+  = note: The array is empty.
+"
+        );
+    }
+
+    #[test]
+    fn shows_labels_in_source_order() {
+        let source = "let a = f(\n  1,\n  2\n);\nlet b = 2;";
+        let diagnostic = Diagnostic::error(Code::INDEX_OUT_OF_BOUNDS, "Index 5 is out of bounds.")
+            .with_label(Label::primary(Span::new(23, 33), "this statement"))
+            .with_label(Label::secondary(Span::new(8, 21), "over several lines"));
+
+        let rendered = TerminalRenderer::plain().render(&[diagnostic], source);
+
+        assert_eq!(
+            rendered,
+            "error[E0310]: Index 5 is out of bounds.
+ --> 5:1
+  |
+1 | let a = f(
+  |         -- over several lines
+...
+5 | let b = 2;
+  | ^^^^^^^^^^ this statement
+"
+        );
+    }
+
+    #[test]
+    fn labels_on_the_same_line_share_it() {
+        let source = "let a = 1 2;";
+        let diagnostic = Diagnostic::error(Code::EXPECTED_TOKEN, "Expected `;`, but found `2`")
+            .with_label(Label::primary(Span::new(10, 11), "expected `;`"))
+            .with_label(Label::secondary(Span::new(0, 9), ""))
+            .with_help("Statements end with a `;`.");
+
+        let rendered = TerminalRenderer::plain().render(&[diagnostic], source);
+
+        assert_eq!(
+            rendered,
+            "error[E0201]: Expected `;`, but found `2`
+ --> 1:11
+  |
+1 | let a = 1 2;
+  |           ^ expected `;`
+  | ---------
+  = help: Statements end with a `;`.
+"
+        );
+    }
+
+    #[test]
+    fn underlines_the_end_of_input() {
+        let source = "let a =";
+        let diagnostic = Diagnostic::error(Code::EXPECTED_EXPRESSION, "Expected an expression")
+            .with_label(Label::primary(Span::new(7, 7), ""));
+
+        let rendered = TerminalRenderer::plain().render(&[diagnostic], source);
+
+        assert!(rendered.ends_with("1 | let a =\n  |        ^\n"));
+    }
+
+    #[test]
+    fn colours_only_when_asked() {
+        let diagnostics = [Diagnostic::error(Code::SYNTAX_ERROR, "Bad")];
+
+        assert!(!TerminalRenderer::plain()
+            .render(&diagnostics, "")
+            .contains('\x1b'));
+        assert!(TerminalRenderer::coloured()
+            .render(&diagnostics, "")
+            .contains("\x1b[1;31merror[E0318]\x1b[0m"));
+    }
+}
@@ -1,26 +1,39 @@
-use ast::Stmt;
-use gc::{Finalize, Trace};
+use std::rc::Rc;
 
+use ast::Program;
+use gc::{Finalize, Gc, Trace};
+
+use crate::generator::Generator;
+use crate::scope::Scope;
 use crate::{BlockExit, Context, Error, GcValue, Value};
 
 pub trait Callable: Trace + Finalize {
     fn call(&self, context: &mut Context, args: &[GcValue]) -> Result<GcValue, Error>;
 }
 
-#[derive(Debug, Clone, Trace, Finalize)]
+#[derive(Debug, Trace, Finalize)]
 pub struct InterpretedFn {
-    stack_height: usize,
+    /// The scope the function was defined in, which it keeps alive for as long as it exists.
+    scope: Gc<Scope>,
     prop_idents: Vec<String>,
+    /// Shared with any generators that are still running it.
     #[unsafe_ignore_trace]
-    body: Vec<Stmt>,
+    body: Rc<Program>,
+    is_generator: bool,
 }
 
 impl InterpretedFn {
-    pub fn new(stack_height: usize, prop_idents: Vec<String>, body: Vec<Stmt>) -> Self {
+    pub(crate) fn new(
+        scope: Gc<Scope>,
+        prop_idents: Vec<String>,
+        body: Program,
+        is_generator: bool,
+    ) -> Self {
         Self {
-            stack_height,
+            scope,
             prop_idents,
-            body,
+            body: Rc::new(body),
+            is_generator,
         }
     }
 }
@@ -34,17 +47,18 @@ impl Callable for InterpretedFn {
             ));
         }
 
-        let popped = context.stack.pop_until_index(self.stack_height);
-        context.stack.open_frame();
+        let scope = Scope::new(Some(self.scope.clone()));
 
         for (ident, value) in self.prop_idents.iter().zip(args.iter()) {
-            context.stack.push_value(ident.clone(), value.clone());
+            scope.push_value(ident.clone(), value.clone());
         }
 
-        let res = context.eval_program(&self.body)?;
+        // Generators don't run anything until the first item is asked for.
+        if self.is_generator {
+            return Ok(Value::iterator(Generator::new(scope, self.body.clone())).into_gc());
+        }
 
-        context.stack.pop_frame();
-        context.stack.push_popped_stack(popped);
+        let res = context.with_scope(scope, |context| context.eval_program(&self.body))?;
 
         if let BlockExit::Returned(r) = res {
             return Ok(r.unwrap_or_else(|| (Value::Null).into_gc()));
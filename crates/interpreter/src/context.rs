@@ -1,16 +1,16 @@
 use std::collections::{HashMap, VecDeque};
 use std::fmt::{Display, Formatter};
-use std::rc::Rc;
 
 use ast::{
-    AssignOpKind, BinaryOp, Expr, FnCall, FnDecl, Member, Program, Stmt, VarAssign, VarDecl,
-    WhileLoop,
+    AssignOpKind, BinaryOp, Expr, ExprKind, FnCall, FnDecl, ForLoop, Member, MethodCall, Program,
+    Range, Span, Stmt, StmtKind, TemplatePart, UnaryOp, VarAssign, VarDecl, WhileLoop,
 };
-use gc::GcCell;
+use gc::Gc;
 use is_macro::Is;
 
-use crate::error::Error;
-use crate::stack::{FoundIdent, Stack};
+use crate::error::{Error, RuntimeError, TraceFrame};
+use crate::iterator::{advance, has_next_method, iter_value, ForItems, RangeIter};
+use crate::scope::Scope;
 use crate::stdlib::add_stdlib;
 use crate::value::{GcValue, ShallowValue, Value};
 use crate::{Callable, InterpretedFn, NativeFn};
@@ -40,28 +40,90 @@ impl Display for BlockExit {
     }
 }
 
+/// Creates the scope for one pass through the body of a `for` loop, with the loop's identifiers bound.
+pub(crate) fn for_loop_scope(
+    for_loop: &ForLoop,
+    parent: Gc<Scope>,
+    binds_key: bool,
+    (key, item): (GcValue, GcValue),
+) -> Gc<Scope> {
+    let scope = Scope::new(Some(parent));
+
+    match &for_loop.second {
+        Some(second) => {
+            scope.push_value(for_loop.first.clone(), key);
+            scope.push_value(second.clone(), item.shallow_copy());
+        }
+        None if binds_key => scope.push_value(for_loop.first.clone(), key),
+        None => scope.push_value(for_loop.first.clone(), item.shallow_copy()),
+    }
+
+    scope
+}
+
+/// The target of an assignment.
+enum Place {
+    /// A variable or array item that already exists, which is overwritten in place
+    Value(GcValue),
+    /// A key of an object, which may not exist yet
+    ObjectKey(GcValue, String),
+}
+
 #[derive(Clone)]
 pub struct Context {
-    pub stack: Stack<GcValue>,
+    /// Holds native functions, which scripts are allowed to shadow.
+    builtins: Gc<Scope>,
+    /// The innermost scope of whatever is currently being evaluated.
+    scope: Gc<Scope>,
+    /// The Thrax functions currently being called, with the innermost last.
+    call_stack: Vec<TraceFrame>,
+    /// Where the error currently being returned happened, along with the call stack at that point.
+    ///
+    /// Only the innermost expression or statement that fails records this, everything it is part of
+    /// just passes the error along.
+    error_location: Option<(Span, Vec<TraceFrame>)>,
 }
 
 impl Context {
     pub fn new() -> Self {
+        let builtins = Scope::new(None);
+
         Self {
-            stack: Stack::new(),
+            scope: Scope::new(Some(builtins.clone())),
+            builtins,
+            call_stack: Vec::new(),
+            error_location: None,
         }
     }
 
     pub fn add_native_fn(&mut self, ident: impl ToString, native_fn: NativeFn) {
-        self.stack.push_value(
-            ident.to_string(),
-            Value::Callable(Rc::new(GcCell::new(native_fn))).into_gc(),
-        )
+        self.builtins
+            .push_value(ident.to_string(), Value::callable(native_fn).into_gc())
+    }
+
+    pub fn add_callable(&mut self, ident: impl ToString, callable: impl Callable + 'static) {
+        self.builtins
+            .push_value(ident.to_string(), Value::callable(callable).into_gc())
     }
 
-    pub fn add_callable(&mut self, ident: impl ToString, callable: Rc<GcCell<dyn Callable>>) {
-        self.stack
-            .push_value(ident.to_string(), Value::Callable(callable).into_gc())
+    /// Evaluates `f` inside of `scope`, going back to the current scope afterwards, even if `f` fails.
+    pub(crate) fn with_scope<T>(
+        &mut self,
+        scope: Gc<Scope>,
+        f: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let previous = std::mem::replace(&mut self.scope, scope);
+        let res = f(self);
+        self.scope = previous;
+
+        res
+    }
+
+    /// Evaluates a block inside of a new scope, so its variables are dropped at the end.
+    fn eval_block(&mut self, block: &Program) -> Result<BlockExit, Error> {
+        let scope = Scope::new(Some(self.scope.clone()));
+
+        self.with_scope(scope, |context| context.eval_program(block))
     }
 
     /// Courtesey wrapper for [`crate::stdlib::add_stdlib`]
@@ -74,6 +136,30 @@ impl Context {
         self
     }
 
+    /// Evaluates a whole program, reporting where it failed if it does.
+    ///
+    /// Use this over [`Self::eval_program`] for anything that shows errors to users.
+    pub fn run_program(&mut self, program: &Program) -> Result<BlockExit, RuntimeError> {
+        self.error_location = None;
+
+        self.eval_program(program).map_err(|error| {
+            let (span, backtrace) = self.error_location.take().unwrap_or_default();
+
+            RuntimeError {
+                error,
+                span,
+                backtrace,
+            }
+        })
+    }
+
+    /// Remembers where an error happened, unless something inside of `span` already did.
+    fn record_error_location(&mut self, span: Span) {
+        if self.error_location.is_none() {
+            self.error_location = Some((span, self.call_stack.clone()));
+        }
+    }
+
     pub fn eval_program(&mut self, program: &Program) -> Result<BlockExit, Error> {
         for stmt in program {
             let res = self.eval_stmt(stmt)?;
@@ -87,19 +173,34 @@ impl Context {
     }
 
     pub fn eval_stmt(&mut self, stmt: &Stmt) -> Result<BlockExit, Error> {
+        let res = self.eval_stmt_kind(&stmt.kind);
+
+        if res.is_err() {
+            self.record_error_location(stmt.span);
+        }
+
+        res
+    }
+
+    fn eval_stmt_kind(&mut self, stmt: &StmtKind) -> Result<BlockExit, Error> {
         match stmt {
-            Stmt::VarDecl(var_decl) => self.eval_var_decl(var_decl).map(|_| BlockExit::Completed),
-            Stmt::VarAssign(var_assign) => self
+            StmtKind::VarDecl(var_decl) => {
+                self.eval_var_decl(var_decl).map(|_| BlockExit::Completed)
+            }
+            StmtKind::VarAssign(var_assign) => self
                 .eval_var_assign(var_assign)
                 .map(|_| BlockExit::Completed),
-            Stmt::FnDecl(fn_decl) => self.eval_fn_decl(fn_decl).map(|_| BlockExit::Completed),
-            Stmt::Expr(expr) => self
+            StmtKind::FnDecl(fn_decl) => self.eval_fn_decl(fn_decl).map(|_| BlockExit::Completed),
+            StmtKind::Expr(expr) => self
                 .eval_expr(expr)
                 .map(|_| ())
                 .map(|_| BlockExit::Completed),
-            Stmt::IfElse(if_else) => self.eval_if_else(if_else),
-            Stmt::WhileLoop(while_loop) => self.eval_while_loop(while_loop),
-            Stmt::BlockExit(block_exit) => {
+            StmtKind::IfElse(if_else) => self.eval_if_else(if_else),
+            StmtKind::WhileLoop(while_loop) => self.eval_while_loop(while_loop),
+            StmtKind::ForLoop(for_loop) => self.eval_for_loop(for_loop),
+            StmtKind::Yield(_) => Err(Error::YieldOutsideGenerator),
+            StmtKind::Error => Err(Error::SyntaxError),
+            StmtKind::BlockExit(block_exit) => {
                 let exit = match block_exit {
                     ast::BlockExit::FnReturn(res) => {
                         if let Some(expr) = res {
@@ -118,41 +219,84 @@ impl Context {
     }
 
     pub fn eval_expr(&mut self, expr: &Expr) -> Result<GcValue, Error> {
-        match expr {
-            Expr::Ident(i) => self.find_with_ident(i).map(|v| v.value),
-            Expr::NumberLiteral(n) => Ok(Value::Number(*n).into_gc()),
-            Expr::StringLiteral(s) => Ok(Value::String(s.clone()).into_gc()),
-            Expr::BoolLiteral(b) => Ok(Value::Bool(*b).into_gc()),
-            Expr::ArrayLiteral(arr) => self.eval_array_lit(arr),
-            Expr::ObjectLiteral(obj) => self.eval_object_lit(obj),
-            Expr::BinaryOp(bin_op) => self.eval_binary_op(bin_op),
-            Expr::FnCall(f) => self.run_fn(f),
-            Expr::Member(m) => self.eval_member(m),
+        let res = self.eval_expr_kind(expr);
+
+        if res.is_err() {
+            self.record_error_location(expr.span);
+        }
+
+        res
+    }
+
+    fn eval_expr_kind(&mut self, expr: &Expr) -> Result<GcValue, Error> {
+        match &expr.kind {
+            ExprKind::Ident(i) => self.find_with_ident(i),
+            ExprKind::NumberLiteral(n) => Ok(Value::Number(*n).into_gc()),
+            ExprKind::StringLiteral(s) => Ok(Value::String(s.clone()).into_gc()),
+            ExprKind::Template(parts) => self.eval_template(parts),
+            ExprKind::BoolLiteral(b) => Ok(Value::Bool(*b).into_gc()),
+            ExprKind::NullLiteral => Ok(Value::Null.into_gc()),
+            ExprKind::ArrayLiteral(arr) => self.eval_array_lit(arr),
+            ExprKind::ObjectLiteral(obj) => self.eval_object_lit(obj),
+            ExprKind::UnaryOp(unary_op) => self.eval_unary_op(unary_op),
+            ExprKind::BinaryOp(bin_op) => self.eval_binary_op(bin_op),
+            ExprKind::FnCall(f) => self.run_fn(f, expr.span),
+            ExprKind::MethodCall(m) => self.eval_method_call(m, expr.span),
+            ExprKind::Member(m) => self.eval_member(m),
+            ExprKind::Range(range) => self.eval_range(range),
+            ExprKind::Spread(_) => {
+                unreachable!("The parser only allows spread in array items and arguments.")
+            }
+            ExprKind::Function(f) => Ok(Value::callable(InterpretedFn::new(
+                self.scope.clone(),
+                f.prop_idents.clone(),
+                f.body.clone(),
+                f.is_generator,
+            ))
+            .into_gc()),
         }
     }
 
     fn eval_var_decl(&mut self, var_decl: &VarDecl) -> Result<(), Error> {
-        let Err(_) = self.find_with_ident(&var_decl.ident) else {
+        if self.is_declared(&var_decl.ident) {
             return Err(Error::Redeclaration(var_decl.ident.clone()));
-        };
+        }
 
         let initialized = self.eval_expr(&var_decl.initializer)?.shallow_copy();
 
-        self.stack.push_value(var_decl.ident.clone(), initialized);
+        self.scope.push_value(var_decl.ident.clone(), initialized);
 
         Ok(())
     }
 
     fn eval_var_assign(&mut self, var_assign: &VarAssign) -> Result<(), Error> {
-        let new_value = self.eval_expr(&var_assign.value)?.shallow_copy();
-        let new_value = new_value.borrow();
+        // Cloned out of the cell, since it could be the same cell as the target, like in `a = a`.
+        let new_value = self.eval_expr(&var_assign.value)?.borrow().clone();
+
+        let value = match self.eval_place(&var_assign.to)? {
+            Place::Value(value) => value,
+            Place::ObjectKey(obj, key) => {
+                let mut obj = obj.borrow_mut();
+                let Value::Object(obj) = &mut *obj else {
+                    unreachable!("Only objects are resolved to object keys.")
+                };
+
+                match (obj.get(&key), var_assign.op) {
+                    (Some(value), _) => value.clone(),
+                    (None, AssignOpKind::NoOp) => {
+                        obj.insert(key, new_value.into_gc());
+                        return Ok(());
+                    }
+                    (None, AssignOpKind::Op(_)) => return Err(Error::ObjectMissingKey(key)),
+                }
+            }
+        };
 
-        let value = self.eval_expr(&var_assign.to)?;
         let mut value = value.borrow_mut();
 
         match var_assign.op {
             AssignOpKind::NoOp => {
-                *value = new_value.clone();
+                *value = new_value;
             }
             AssignOpKind::Op(op) => {
                 let arith_res = value.run_binary_op(&new_value, op)?;
@@ -164,35 +308,100 @@ impl Context {
         Ok(())
     }
 
+    /// Finds what the target of an assignment refers to, without requiring object keys to exist yet.
+    fn eval_place(&mut self, target: &Expr) -> Result<Place, Error> {
+        let member = match &target.kind {
+            ExprKind::Ident(ident) => {
+                return self
+                    .scope
+                    .find_with_ident(ident)
+                    .map(Place::Value)
+                    .ok_or_else(|| Error::Undeclared(ident.to_string()))
+            }
+            ExprKind::Member(member) => member,
+            _ => return Err(Error::InvalidAssignmentTarget),
+        };
+
+        let parent = self.eval_expr(&member.parent)?;
+        let child = self.eval_expr(&member.child)?;
+        let child = child.borrow();
+
+        let place = match (&*parent.borrow(), &*child) {
+            (Value::Array(arr), Value::Number(index)) => {
+                if index.fract() != 0. {
+                    return Err(Error::ExpectedInteger(*index));
+                }
+
+                if *index < 0. || *index >= arr.len() as f64 {
+                    return Err(Error::AssignmentOutOfBounds(*index, arr.len()));
+                }
+
+                Place::Value(arr[*index as usize].clone())
+            }
+            (Value::Array(_), child) => {
+                return Err(Error::TypeError(ShallowValue::Number, child.as_shallow()))
+            }
+            (Value::Object(_), Value::String(key)) => Place::ObjectKey(parent.clone(), key.clone()),
+            (Value::Object(_), child) => {
+                return Err(Error::TypeError(ShallowValue::String, child.as_shallow()))
+            }
+            (parent, _) => return Err(Error::CannotAssignIndex(parent.as_shallow())),
+        };
+
+        Ok(place)
+    }
+
     fn eval_fn_decl(&mut self, fn_decl: &FnDecl) -> Result<(), Error> {
-        let Err(_) = self.find_with_ident(&fn_decl.ident) else {
+        if self.is_declared(&fn_decl.ident) {
             return Err(Error::Redeclaration(fn_decl.ident.clone()));
-        };
+        }
 
-        self.stack.push_value(
+        // The function captures the scope it is declared in, so it can see itself and anything declared after it.
+        self.scope.push_value(
             fn_decl.ident.clone(),
-            Value::Callable(Rc::new(GcCell::new(InterpretedFn::new(
-                self.stack.value_len(),
+            Value::callable(InterpretedFn::new(
+                self.scope.clone(),
                 fn_decl.prop_idents.clone(),
                 fn_decl.body.clone(),
-            ))))
+                fn_decl.is_generator,
+            ))
             .into_gc(),
         );
 
         Ok(())
     }
 
+    /// Evaluates the condition of an `if` or `while`, which has to be a bool.
+    pub(crate) fn eval_condition(&mut self, condition: &Expr) -> Result<bool, Error> {
+        let res = self.eval_expr(condition)?;
+        let res = res.borrow();
+
+        match *res {
+            Value::Bool(b) => Ok(b),
+            _ => Err(Error::TypeError(ShallowValue::Bool, res.as_shallow())),
+        }
+    }
+
     fn eval_while_loop(&mut self, while_loop: &WhileLoop) -> Result<BlockExit, Error> {
-        while let Value::Bool(true) = {
-            let res = self.eval_expr(&while_loop.condition)?;
-            let res = res.borrow();
-            res.equals(&Value::Bool(true))?
-        } {
-            self.stack.open_frame();
+        while self.eval_condition(&while_loop.condition)? {
+            let res = self.eval_block(&while_loop.body)?;
+
+            match res {
+                BlockExit::Returned(r) => return Ok(BlockExit::Returned(r)),
+                BlockExit::Break => return Ok(BlockExit::Completed),
+                _ => (),
+            }
+        }
+
+        Ok(BlockExit::Completed)
+    }
 
-            let res = self.eval_program(&while_loop.body)?;
+    fn eval_for_loop(&mut self, for_loop: &ForLoop) -> Result<BlockExit, Error> {
+        let mut items = self.eval_for_items(for_loop)?;
 
-            self.stack.pop_frame();
+        while let Some(next) = items.next(self)? {
+            let scope = for_loop_scope(for_loop, self.scope.clone(), items.binds_key(), next);
+            let res = self.with_scope(scope, |context| context.eval_program(&for_loop.body))?;
 
             match res {
                 BlockExit::Returned(r) => return Ok(BlockExit::Returned(r)),
@@ -204,31 +413,79 @@ impl Context {
         Ok(BlockExit::Completed)
     }
 
-    fn eval_if_else(&mut self, if_else: &ast::IfElse) -> Result<BlockExit, Error> {
-        let branch = match {
-            let res = self.eval_expr(&if_else.condition)?;
-            let res = res.borrow();
-            res.equals(&Value::Bool(true))?
-        } {
-            Value::Bool(true) => &if_else.true_branch,
-            Value::Bool(false) => &if_else.else_branch,
-            _ => panic!(),
+    /// Evaluates what a `for` loop iterates over.
+    pub(crate) fn eval_for_items(&mut self, for_loop: &ForLoop) -> Result<ForItems, Error> {
+        // Ranges are counted through directly, rather than building an array of every number first.
+        let iterable = match &for_loop.iterable.kind {
+            ExprKind::Range(range) => {
+                let (start, end) = self.eval_range_bounds(range)?;
+                Value::iterator(RangeIter::new(start, end)).into_gc()
+            }
+            _ => self.eval_expr(&for_loop.iterable)?,
         };
 
-        self.stack.open_frame();
+        // Objects without a `next` method are iterated over by their entries.
+        // They are sorted, so the order doesn't change between runs.
+        if let Value::Object(obj) = &*iterable.borrow() {
+            if !has_next_method(obj) {
+                let mut entries: Vec<_> = obj.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+
+                return Ok(ForItems::Entries(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (Value::String(key.clone()).into_gc(), value.clone()))
+                        .collect(),
+                ));
+            }
+        }
+
+        Ok(ForItems::Iter {
+            iter: iter_value(&iterable)?,
+            index: 0,
+        })
+    }
 
-        let res = self.eval_program(branch);
+    /// Ranges used outside of a `for` loop are collected into an array.
+    fn eval_range(&mut self, range: &Range) -> Result<GcValue, Error> {
+        let (start, end) = self.eval_range_bounds(range)?;
 
-        self.stack.pop_frame();
+        let mut numbers = RangeIter::new(start, end);
+        let items = std::iter::from_fn(|| numbers.next_number())
+            .map(|n| Value::Number(n).into_gc())
+            .collect();
 
-        res
+        Ok(Value::Array(items).into_gc())
+    }
+
+    fn eval_range_bounds(&mut self, range: &Range) -> Result<(f64, f64), Error> {
+        let mut bounds = [0.; 2];
+
+        for (bound, expr) in bounds.iter_mut().zip([&range.start, &range.end]) {
+            match &*self.eval_expr(expr)?.borrow() {
+                Value::Number(n) => *bound = *n,
+                other => return Err(Error::TypeError(ShallowValue::Number, other.as_shallow())),
+            }
+        }
+
+        Ok((bounds[0], bounds[1]))
+    }
+
+    fn eval_if_else(&mut self, if_else: &ast::IfElse) -> Result<BlockExit, Error> {
+        let branch = if self.eval_condition(&if_else.condition)? {
+            &if_else.true_branch
+        } else {
+            &if_else.else_branch
+        };
+
+        self.eval_block(branch)
     }
 
     fn eval_member(&mut self, member: &Member) -> Result<GcValue, Error> {
         let child = self.eval_expr(&member.child)?;
-        let child = child.borrow();
-
         let parent = self.eval_expr(&member.parent)?;
+
+        let child = child.borrow();
         let parent = parent.borrow_mut();
 
         match &*parent {
@@ -275,15 +532,47 @@ impl Context {
         }
     }
 
-    fn eval_array_lit(&mut self, arr: &[Expr]) -> Result<GcValue, Error> {
-        let mut results = VecDeque::with_capacity(arr.len());
-        for expr in arr.iter() {
-            let result = self.eval_expr(expr)?;
-            results.push_back(result);
+    fn eval_template(&mut self, parts: &[TemplatePart]) -> Result<GcValue, Error> {
+        let mut text = String::new();
+
+        for part in parts {
+            match part {
+                TemplatePart::Literal(literal) => text.push_str(literal),
+                TemplatePart::Expr(expr) => {
+                    let value = self.eval_expr(expr)?;
+                    text.push_str(&value.to_string());
+                }
+            }
         }
+
+        Ok(Value::String(text).into_gc())
+    }
+
+    fn eval_array_lit(&mut self, arr: &[Expr]) -> Result<GcValue, Error> {
+        let results = VecDeque::from(self.eval_list(arr)?);
         Ok((Value::Array(results)).into_gc())
     }
 
+    /// Evaluates the items of an array literal or the arguments of a call, expanding any that are spread.
+    fn eval_list(&mut self, exprs: &[Expr]) -> Result<Vec<GcValue>, Error> {
+        let mut results = Vec::with_capacity(exprs.len());
+
+        for expr in exprs {
+            let ExprKind::Spread(spread) = &expr.kind else {
+                results.push(self.eval_expr(expr)?);
+                continue;
+            };
+
+            let iter = iter_value(&self.eval_expr(spread)?)?;
+
+            while let Some(item) = advance(&iter, self)? {
+                results.push(item);
+            }
+        }
+
+        Ok(results)
+    }
+
     fn eval_object_lit(&mut self, obj: &HashMap<String, Expr>) -> Result<GcValue, Error> {
         let mut results = HashMap::with_capacity(obj.len());
 
@@ -295,11 +584,22 @@ impl Context {
         Ok(Value::Object(results).into_gc())
     }
 
+    fn eval_unary_op(&mut self, unary_op: &UnaryOp) -> Result<GcValue, Error> {
+        let operand = self.eval_expr(&unary_op.operand)?;
+        let res = operand.borrow().run_unary_op(unary_op.kind)?;
+
+        Ok(res.into_gc())
+    }
+
     fn eval_binary_op(&mut self, bin_op: &BinaryOp) -> Result<GcValue, Error> {
         let BinaryOp { kind, a, b } = bin_op;
 
-        let c_a = self.eval_expr(a)?;
-        let c_a = c_a.borrow();
+        if kind.is_short_circuiting() {
+            return self.eval_short_circuiting_op(bin_op);
+        }
+
+        // Copied out before the right side runs, since it can assign to the variable the left side read
+        let c_a = self.eval_expr(a)?.borrow().clone();
         let c_b = self.eval_expr(b)?;
         let c_b = c_b.borrow();
 
@@ -308,36 +608,122 @@ impl Context {
         Ok((arith_res).into_gc())
     }
 
-    fn run_fn(&mut self, fn_call: &FnCall) -> Result<GcValue, Error> {
-        let mut args = Vec::with_capacity(fn_call.args.len());
+    /// Evaluates `&&` or `||`, skipping the right side if the left side already decides the result.
+    fn eval_short_circuiting_op(&mut self, bin_op: &BinaryOp) -> Result<GcValue, Error> {
+        let BinaryOp { kind, a, b } = bin_op;
+
+        let decided_by = kind.is_or();
 
-        for arg in &fn_call.args {
-            let result = self.eval_expr(arg)?;
-            args.push(result.shallow_copy());
+        match *self.eval_expr(a)?.borrow() {
+            Value::Bool(a) if a == decided_by => return Ok(Value::Bool(a).into_gc()),
+            Value::Bool(_) => (),
+            ref other => return Err(Error::TypeError(ShallowValue::Bool, other.as_shallow())),
         }
 
-        let fn_def = {
-            let FoundIdent {
-                value: definition, ..
-            } = self.find_with_ident(&fn_call.ident)?;
+        let c_b = self.eval_expr(b)?;
+        let c_b = c_b.borrow();
+
+        match *c_b {
+            Value::Bool(b) => Ok(Value::Bool(b).into_gc()),
+            _ => Err(Error::TypeError(ShallowValue::Bool, c_b.as_shallow())),
+        }
+    }
+
+    fn run_fn(&mut self, fn_call: &FnCall, span: Span) -> Result<GcValue, Error> {
+        let definition = self.eval_expr(&fn_call.callee)?;
+        let args = self.eval_args(&fn_call.args)?;
+
+        let frame = TraceFrame {
+            name: fn_call.callee.kind.as_ident().cloned(),
+            call_site: span,
+        };
+
+        self.call_traced(frame, &definition, &args)
+    }
+
+    /// Calls a method, passing the receiver as the first argument.
+    ///
+    /// Objects are searched for a function under the method's name first.
+    /// Otherwise, the method is looked up like any other function, so `arr.push(x)` is the same as `push(arr, x)`.
+    fn eval_method_call(&mut self, method_call: &MethodCall, span: Span) -> Result<GcValue, Error> {
+        let receiver = self.eval_expr(&method_call.receiver)?.shallow_copy();
 
+        let own_method = match &*receiver.borrow() {
+            Value::Object(obj) => obj.get(&method_call.method).cloned(),
+            _ => None,
+        };
+
+        let definition = match own_method {
+            Some(definition) => definition,
+            None => self.find_with_ident(&method_call.method).map_err(|_| {
+                Error::UndefinedMethod(receiver.borrow().as_shallow(), method_call.method.clone())
+            })?,
+        };
+
+        let mut args = vec![receiver];
+        args.append(&mut self.eval_args(&method_call.args)?);
+
+        let frame = TraceFrame {
+            name: Some(method_call.method.clone()),
+            call_site: span,
+        };
+
+        self.call_traced(frame, &definition, &args)
+    }
+
+    /// Calls a function from a call expression, keeping track of it for backtraces.
+    fn call_traced(
+        &mut self,
+        frame: TraceFrame,
+        definition: &GcValue,
+        args: &[GcValue],
+    ) -> Result<GcValue, Error> {
+        self.call_stack.push(frame);
+        let res = self.call_value(definition, args);
+        self.call_stack.pop();
+
+        res
+    }
+
+    fn eval_args(&mut self, args: &[Expr]) -> Result<Vec<GcValue>, Error> {
+        let results = self.eval_list(args)?;
+
+        Ok(results.iter().map(GcValue::shallow_copy).collect())
+    }
+
+    /// Calls a function value, like a callback passed to a native function.
+    ///
+    /// Taking the whole value, rather than just the callable inside of it, keeps everything the function
+    /// captured reachable by the GC while it runs.
+    pub fn call_value(&mut self, definition: &GcValue, args: &[GcValue]) -> Result<GcValue, Error> {
+        let fn_def = {
             let definition = definition.borrow();
 
-            let Value::Callable(df) = &*definition else{
-                        return Err(Error::TypeError(ShallowValue::Callable, definition.as_shallow()));
-                    };
+            let Value::Callable(df) = &*definition else {
+                return Err(Error::TypeError(
+                    ShallowValue::Callable,
+                    definition.as_shallow(),
+                ));
+            };
 
             df.clone()
         };
 
         let fn_def = fn_def.borrow();
-        fn_def.call(self, &args)
+        fn_def.call(self, args)
+    }
+
+    /// Whether a variable has already been declared by the script, ignoring builtins.
+    fn is_declared(&self, ident: &str) -> bool {
+        self.scope
+            .find_with_ident_below(ident, &self.builtins)
+            .is_some()
     }
 
-    fn find_with_ident(&self, ident: &str) -> Result<FoundIdent<GcValue>, Error> {
-        self.stack
+    fn find_with_ident(&self, ident: &str) -> Result<GcValue, Error> {
+        self.scope
             .find_with_ident(ident)
-            .ok_or_else(|| Error::Undeclared(ident.to_string()))
+            .ok_or_else(|| Error::UndefinedVariable(ident.to_string()))
     }
 }
 
@@ -1,7 +1,59 @@
-use ast::BinaryOpKind;
+use std::fmt::{Display, Formatter};
+
+use ast::{BinaryOpKind, Span};
+use diagnostics::{Code, Diagnostic, Label};
 
 use crate::value::ShallowValue;
 
+/// An [`Error`] that stopped a program, along with where it happened.
+#[derive(Debug, thiserror::Error)]
+#[error("{error}")]
+pub struct RuntimeError {
+    pub error: Error,
+    /// The innermost expression or statement that failed.
+    pub span: Span,
+    /// The Thrax functions that were running when the error happened, with the innermost last.
+    pub backtrace: Vec<TraceFrame>,
+}
+
+/// A call to a Thrax function that hasn't returned yet.
+#[derive(Debug, Clone)]
+pub struct TraceFrame {
+    /// The variable or method the function was called through, if it was called by name.
+    pub name: Option<String>,
+    /// The whole call expression.
+    pub call_site: Span,
+}
+
+impl RuntimeError {
+    /// Describes the error, with a label on where it happened and on each call that led there, innermost first.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(self.error.code(), self.error.to_string())
+            .with_label(Label::primary(self.span, ""));
+
+        for frame in self.backtrace.iter().rev() {
+            diagnostic = diagnostic.with_label(Label::secondary(
+                frame.call_site,
+                format!("{frame} called here"),
+            ));
+        }
+
+        match self.error.help() {
+            Some(help) => diagnostic.with_help(help),
+            None => diagnostic,
+        }
+    }
+}
+
+impl Display for TraceFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "`{name}`"),
+            None => write!(f, "anonymous function"),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("A value as already been assigned to {0}.")]
@@ -17,10 +69,8 @@ pub enum Error {
     TypeError(ShallowValue, ShallowValue),
     #[error("Attempted to perform a `{:?}` operation with {0} and {1}. This is invalid.", .2)]
     InvalidBinaryOpArgs(ShallowValue, ShallowValue, BinaryOpKind),
-    #[error("Attempted to access non-existant variable {0}.")]
-    UndefinedStackAccess(String),
-    #[error("Attempted to access non-existant heap item {0}.")]
-    UndefinedHeapAccess(usize),
+    #[error("Attempted to read undeclared variable {0}.")]
+    UndefinedVariable(String),
     /// Represent that function is being supplied too many arguments.
     ///
     /// 0 => # of args requested
@@ -30,10 +80,73 @@ pub enum Error {
     IncorrectArgumentCount(usize, usize),
     #[error("Expected integer value, got {0}")]
     ExpectedInteger(f64),
+    #[error("Cannot shift by {0}, shift amounts must be between 0 and 63.")]
+    ShiftOutOfRange(f64),
     #[error("Requested string or integer index {0} is out of bounds.")]
     IndexOutOfBounds(usize),
+    /// 0 => Index being assigned to
+    ///
+    /// 1 => Length of the array
+    #[error("Cannot assign to index {0} of an array with {1} items.")]
+    AssignmentOutOfBounds(f64, usize),
+    #[error("Cannot assign to an index of a {0}.")]
+    CannotAssignIndex(ShallowValue),
+    #[error("Only variables, array items and object keys can be assigned to.")]
+    InvalidAssignmentTarget,
     #[error("Requested key {0} in object does not exist in object.")]
     ObjectMissingKey(String),
+    #[error("{0} has no method named {1}.")]
+    UndefinedMethod(ShallowValue, String),
+    #[error("`yield` can only be used in the body of a generator function.")]
+    YieldOutsideGenerator,
+    #[error("Cannot run a statement that failed to parse.")]
+    SyntaxError,
+    #[error("Cannot iterate over a {0}.")]
+    CannotIterate(ShallowValue),
     #[error("Requestion type {0} cannot be indexed.")]
     CannotIndexType(ShallowValue),
+    #[error("Cannot advance an iterator while it is already producing an item.")]
+    IteratorAlreadyRunning,
+}
+
+impl Error {
+    pub fn code(&self) -> Code {
+        match self {
+            Error::Redeclaration(_) => Code::REDECLARATION,
+            Error::Undeclared(_) => Code::UNDECLARED,
+            Error::TypeError(..) => Code::TYPE_ERROR,
+            Error::InvalidBinaryOpArgs(..) => Code::INVALID_BINARY_OP_ARGS,
+            Error::UndefinedVariable(_) => Code::UNDEFINED_VARIABLE,
+            Error::IncorrectArgumentCount(..) => Code::INCORRECT_ARGUMENT_COUNT,
+            Error::ExpectedInteger(_) => Code::EXPECTED_INTEGER,
+            Error::ShiftOutOfRange(_) => Code::SHIFT_OUT_OF_RANGE,
+            Error::IndexOutOfBounds(_) => Code::INDEX_OUT_OF_BOUNDS,
+            Error::AssignmentOutOfBounds(..) => Code::ASSIGNMENT_OUT_OF_BOUNDS,
+            Error::CannotAssignIndex(_) => Code::CANNOT_ASSIGN_INDEX,
+            Error::InvalidAssignmentTarget => Code::INVALID_ASSIGNMENT_TARGET,
+            Error::ObjectMissingKey(_) => Code::OBJECT_MISSING_KEY,
+            Error::UndefinedMethod(..) => Code::UNDEFINED_METHOD,
+            Error::YieldOutsideGenerator => Code::YIELD_OUTSIDE_GENERATOR,
+            Error::SyntaxError => Code::SYNTAX_ERROR,
+            Error::CannotIterate(_) => Code::CANNOT_ITERATE,
+            Error::CannotIndexType(_) => Code::CANNOT_INDEX_TYPE,
+            Error::IteratorAlreadyRunning => Code::ITERATOR_ALREADY_RUNNING,
+        }
+    }
+
+    /// A suggestion for fixing the error, if there is an obvious one.
+    pub fn help(&self) -> Option<String> {
+        match self {
+            Error::Redeclaration(ident) => Some(format!(
+                "Assign to `{ident}` without `let` to change its value."
+            )),
+            Error::Undeclared(ident) | Error::UndefinedVariable(ident) => {
+                Some(format!("Declare it first with `let {ident} = ...;`."))
+            }
+            Error::YieldOutsideGenerator => {
+                Some("Declare the function with `fn*` to make it a generator.".to_string())
+            }
+            _ => None,
+        }
+    }
 }
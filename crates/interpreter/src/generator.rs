@@ -0,0 +1,259 @@
+use std::rc::Rc;
+
+use ast::{Program, Stmt, StmtKind};
+use gc::{Finalize, Gc, Trace};
+
+use crate::context::for_loop_scope;
+use crate::iterator::ForItems;
+use crate::scope::Scope;
+use crate::{Context, Error, GcValue, ValueIterator};
+
+/// The paused body of a call to a generator function.
+///
+/// The evaluator can't stop halfway through a statement, so a generator keeps its own stack of the blocks
+/// it is inside of and steps through them one statement at a time. `yield` is only allowed as a statement,
+/// so everything besides blocks and jumps out of them is evaluated as usual.
+#[derive(Trace, Finalize)]
+pub(crate) struct Generator {
+    #[unsafe_ignore_trace]
+    body: Rc<Program>,
+    /// The innermost block is last. Empty once the generator has finished.
+    frames: Vec<Frame>,
+}
+
+#[derive(Trace, Finalize)]
+struct Frame {
+    kind: FrameKind,
+    scope: Gc<Scope>,
+    /// The statement to run next in this frame's block.
+    ///
+    /// While a block nested in this one is running, this stays on the statement the nested block belongs to.
+    next: usize,
+}
+
+#[derive(Trace, Finalize)]
+enum FrameKind {
+    /// The body of the function
+    Body,
+    /// The branch of an `if` that was taken, with `true` for the first branch
+    Branch(bool),
+    While,
+    For(ForItems),
+}
+
+impl FrameKind {
+    fn is_loop(&self) -> bool {
+        matches!(self, FrameKind::While | FrameKind::For(_))
+    }
+}
+
+/// What to do after running a statement.
+enum Step {
+    Continue,
+    Yield(GcValue),
+    Finish,
+}
+
+impl Generator {
+    pub(crate) fn new(scope: Gc<Scope>, body: Rc<Program>) -> Self {
+        Self {
+            body,
+            frames: vec![Frame {
+                kind: FrameKind::Body,
+                scope,
+                next: 0,
+            }],
+        }
+    }
+
+    fn step(&mut self, context: &mut Context) -> Result<Step, Error> {
+        let body = self.body.clone();
+        let block = block_of(&body, &self.frames);
+
+        let Some(frame) = self.frames.last() else {
+            return Ok(Step::Finish);
+        };
+
+        let Some(stmt) = block.get(frame.next) else {
+            return self.end_block(context);
+        };
+
+        let scope = frame.scope.clone();
+        let top = self.frames.len() - 1;
+
+        match &stmt.kind {
+            StmtKind::Yield(expr) => {
+                let value = context.with_scope(scope, |context| context.eval_expr(expr))?;
+                self.frames[top].next += 1;
+
+                // Copied, so the body reassigning a variable after yielding it doesn't change the item.
+                return Ok(Step::Yield(value.shallow_copy()));
+            }
+            StmtKind::IfElse(if_else) => {
+                let condition = context.with_scope(scope.clone(), |context| {
+                    context.eval_condition(&if_else.condition)
+                })?;
+
+                self.frames.push(Frame {
+                    kind: FrameKind::Branch(condition),
+                    scope: Scope::new(Some(scope)),
+                    next: 0,
+                });
+            }
+            StmtKind::WhileLoop(while_loop) => {
+                let condition = context.with_scope(scope.clone(), |context| {
+                    context.eval_condition(&while_loop.condition)
+                })?;
+
+                if condition {
+                    self.frames.push(Frame {
+                        kind: FrameKind::While,
+                        scope: Scope::new(Some(scope)),
+                        next: 0,
+                    });
+                } else {
+                    self.frames[top].next += 1;
+                }
+            }
+            StmtKind::ForLoop(for_loop) => {
+                let mut items = context
+                    .with_scope(scope.clone(), |context| context.eval_for_items(for_loop))?;
+
+                match items.next(context)? {
+                    Some(next) => {
+                        let scope = for_loop_scope(for_loop, scope, items.binds_key(), next);
+
+                        self.frames.push(Frame {
+                            kind: FrameKind::For(items),
+                            scope,
+                            next: 0,
+                        });
+                    }
+                    None => self.frames[top].next += 1,
+                }
+            }
+            StmtKind::BlockExit(ast::BlockExit::FnReturn(expr)) => {
+                // There is nowhere for the returned value to go, but it is still evaluated.
+                if let Some(expr) = expr {
+                    context.with_scope(scope, |context| context.eval_expr(expr))?;
+                }
+
+                return Ok(Step::Finish);
+            }
+            StmtKind::BlockExit(ast::BlockExit::Break) => {
+                let Some(loop_frame) = self.innermost_loop() else {
+                    return Ok(Step::Finish);
+                };
+
+                self.frames.truncate(loop_frame);
+                self.frames[loop_frame - 1].next += 1;
+            }
+            StmtKind::BlockExit(ast::BlockExit::Continue) => {
+                let Some(loop_frame) = self.innermost_loop() else {
+                    return Ok(Step::Finish);
+                };
+
+                self.frames.truncate(loop_frame + 1);
+
+                return self.end_block(context);
+            }
+            StmtKind::VarDecl(_)
+            | StmtKind::VarAssign(_)
+            | StmtKind::FnDecl(_)
+            | StmtKind::Expr(_)
+            | StmtKind::Error => {
+                context.with_scope(scope, |context| context.eval_stmt(stmt))?;
+                self.frames[top].next += 1;
+            }
+        }
+
+        Ok(Step::Continue)
+    }
+
+    /// Leaves the innermost block, or starts the next pass through it if it is the body of a loop.
+    fn end_block(&mut self, context: &mut Context) -> Result<Step, Error> {
+        let body = self.body.clone();
+        let depth = self.frames.len();
+
+        // The body of the function is the only frame without a parent.
+        let Some((frame, parents)) = self.frames.split_last_mut().filter(|_| depth > 1) else {
+            return Ok(Step::Finish);
+        };
+
+        let parent = &parents[depth - 2];
+        let stmt = &block_of(&body, parents)[parent.next];
+
+        let next_scope = match (&mut frame.kind, &stmt.kind) {
+            (FrameKind::While, StmtKind::WhileLoop(while_loop)) => {
+                let condition = context.with_scope(parent.scope.clone(), |context| {
+                    context.eval_condition(&while_loop.condition)
+                })?;
+
+                condition.then(|| Scope::new(Some(parent.scope.clone())))
+            }
+            (FrameKind::For(items), StmtKind::ForLoop(for_loop)) => {
+                items.next(context)?.map(|next| {
+                    for_loop_scope(for_loop, parent.scope.clone(), items.binds_key(), next)
+                })
+            }
+            _ => None,
+        };
+
+        match next_scope {
+            Some(scope) => {
+                frame.scope = scope;
+                frame.next = 0;
+            }
+            None => {
+                self.frames.pop();
+                self.frames[depth - 2].next += 1;
+            }
+        }
+
+        Ok(Step::Continue)
+    }
+
+    /// The position in the stack of the innermost loop being run.
+    fn innermost_loop(&self) -> Option<usize> {
+        self.frames.iter().rposition(|frame| frame.kind.is_loop())
+    }
+}
+
+impl ValueIterator for Generator {
+    fn next(&mut self, context: &mut Context) -> Result<Option<GcValue>, Error> {
+        loop {
+            let step = self.step(context);
+
+            match step {
+                Ok(Step::Continue) => (),
+                Ok(Step::Yield(value)) => return Ok(Some(value)),
+                // A generator that has failed can't be resumed either.
+                Ok(Step::Finish) | Err(_) => {
+                    self.frames.clear();
+                    return step.map(|_| None);
+                }
+            }
+        }
+    }
+}
+
+/// Finds the block that the innermost of `frames` is running, by following each frame from the body down.
+fn block_of<'a>(body: &'a Program, frames: &[Frame]) -> &'a [Stmt] {
+    let mut block = &body[..];
+
+    for pair in frames.windows(2) {
+        let [parent, frame] = pair else {
+            unreachable!()
+        };
+
+        block = match (&frame.kind, &block[parent.next].kind) {
+            (FrameKind::Branch(true), StmtKind::IfElse(if_else)) => &if_else.true_branch,
+            (FrameKind::Branch(false), StmtKind::IfElse(if_else)) => &if_else.else_branch,
+            (FrameKind::While, StmtKind::WhileLoop(while_loop)) => &while_loop.body,
+            (FrameKind::For(_), StmtKind::ForLoop(for_loop)) => &for_loop.body,
+            _ => unreachable!("A frame is only pushed for the statement its parent is on."),
+        };
+    }
+
+    block
+}
@@ -0,0 +1,268 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+
+use gc::{Finalize, Gc, GcCell, Trace};
+
+use crate::{Context, Error, GcValue, ShallowValue, Value};
+
+/// Produces values one at a time, for `for` loops, spread and the iterator natives.
+///
+/// Items are only produced when asked for, so an iterator can be arbitrarily long, or even infinite.
+pub trait ValueIterator: Trace + Finalize {
+    /// Returns `None` once there are no items left.
+    fn next(&mut self, context: &mut Context) -> Result<Option<GcValue>, Error>;
+}
+
+/// Gets an iterator over any value that can be iterated over.
+///
+/// Objects can only be iterated over if they implement the iterator protocol,
+/// a `next` method that returns objects like `{ done: false, value: 1 }`.
+pub fn iter_value(value: &GcValue) -> Result<Gc<GcCell<Box<dyn ValueIterator>>>, Error> {
+    let iter: Box<dyn ValueIterator> = match &*value.borrow() {
+        Value::Iterator(iter) => return Ok(iter.clone()),
+        Value::Array(_) => Box::new(ArrayIter {
+            arr: value.clone(),
+            index: 0,
+        }),
+        Value::String(s) => Box::new(StringIter {
+            chars: s.chars().collect(),
+            index: 0,
+        }),
+        Value::Object(obj) if has_next_method(obj) => Box::new(ObjectIter { obj: value.clone() }),
+        other => return Err(Error::CannotIterate(other.as_shallow())),
+    };
+
+    Ok(Gc::new(GcCell::new(iter)))
+}
+
+/// Gets the next item of an iterator, failing if the iterator is already being advanced further up the stack,
+/// like when a generator loops over itself.
+pub fn advance(
+    iter: &Gc<GcCell<Box<dyn ValueIterator>>>,
+    context: &mut Context,
+) -> Result<Option<GcValue>, Error> {
+    let mut iter = iter
+        .try_borrow_mut()
+        .map_err(|_| Error::IteratorAlreadyRunning)?;
+
+    iter.next(context)
+}
+
+pub fn has_next_method(obj: &HashMap<String, GcValue>) -> bool {
+    obj.get("next")
+        .is_some_and(|next| matches!(*next.borrow(), Value::Callable(_)))
+}
+
+/// Reads the array as it goes, so items pushed during iteration are included.
+#[derive(Trace, Finalize)]
+struct ArrayIter {
+    arr: GcValue,
+    index: usize,
+}
+
+impl ValueIterator for ArrayIter {
+    fn next(&mut self, _context: &mut Context) -> Result<Option<GcValue>, Error> {
+        let Value::Array(arr) = &*self.arr.borrow() else {
+            return Err(Error::TypeError(
+                ShallowValue::Array,
+                self.arr.borrow().as_shallow(),
+            ));
+        };
+
+        let item = arr.get(self.index).cloned();
+        self.index += 1;
+
+        Ok(item)
+    }
+}
+
+#[derive(Trace, Finalize)]
+struct StringIter {
+    chars: Vec<char>,
+    index: usize,
+}
+
+impl ValueIterator for StringIter {
+    fn next(&mut self, _context: &mut Context) -> Result<Option<GcValue>, Error> {
+        let item = self
+            .chars
+            .get(self.index)
+            .map(|c| Value::String(c.to_string()).into_gc());
+        self.index += 1;
+
+        Ok(item)
+    }
+}
+
+#[derive(Trace, Finalize)]
+struct ObjectIter {
+    obj: GcValue,
+}
+
+impl ValueIterator for ObjectIter {
+    fn next(&mut self, context: &mut Context) -> Result<Option<GcValue>, Error> {
+        let next = match &*self.obj.borrow() {
+            Value::Object(obj) => obj.get("next").cloned(),
+            _ => None,
+        };
+
+        let Some(next) = next else {
+            return Err(Error::UndefinedMethod(
+                self.obj.borrow().as_shallow(),
+                "next".to_string(),
+            ));
+        };
+
+        let res = context.call_value(&next, std::slice::from_ref(&self.obj))?;
+        let res = res.borrow();
+
+        let Value::Object(res) = &*res else {
+            return Err(Error::TypeError(ShallowValue::Object, res.as_shallow()));
+        };
+
+        if let Some(done) = res.get("done") {
+            if let Value::Bool(true) = *done.borrow() {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(
+            res.get("value")
+                .cloned()
+                .unwrap_or_else(|| Value::Null.into_gc()),
+        ))
+    }
+}
+
+/// Counts up from `start` by one, stopping before `end`.
+///
+/// Each number is worked out from how many have been counted so far, instead of adding one to the last.
+/// Past 2^53, adding one can round back down to the same number, so counting stops once the numbers stop going up.
+#[derive(Trace, Finalize)]
+pub struct RangeIter {
+    start: f64,
+    end: f64,
+    counted: u64,
+    last: Option<f64>,
+}
+
+impl RangeIter {
+    pub fn new(start: f64, end: f64) -> Self {
+        Self {
+            start,
+            end,
+            counted: 0,
+            last: None,
+        }
+    }
+
+    pub fn next_number(&mut self) -> Option<f64> {
+        let n = self.start + self.counted as f64;
+
+        // Also stops on `NaN`, which isn't less than anything.
+        let in_range = n.partial_cmp(&self.end) == Some(Ordering::Less);
+        let increased = self.last.is_none_or(|last| n > last);
+
+        if !in_range || !increased {
+            return None;
+        }
+
+        self.counted += 1;
+        self.last = Some(n);
+
+        Some(n)
+    }
+}
+
+impl ValueIterator for RangeIter {
+    fn next(&mut self, _context: &mut Context) -> Result<Option<GcValue>, Error> {
+        Ok(self.next_number().map(|n| Value::Number(n).into_gc()))
+    }
+}
+
+/// Calls a function on each item of another iterator.
+#[derive(Trace, Finalize)]
+pub struct MapIter {
+    inner: Gc<GcCell<Box<dyn ValueIterator>>>,
+    f: GcValue,
+}
+
+impl MapIter {
+    pub fn new(inner: Gc<GcCell<Box<dyn ValueIterator>>>, f: GcValue) -> Self {
+        Self { inner, f }
+    }
+}
+
+impl ValueIterator for MapIter {
+    fn next(&mut self, context: &mut Context) -> Result<Option<GcValue>, Error> {
+        let Some(item) = advance(&self.inner, context)? else {
+            return Ok(None);
+        };
+
+        context.call_value(&self.f, &[item]).map(Some)
+    }
+}
+
+/// Skips the items of another iterator that a function doesn't return `true` for.
+#[derive(Trace, Finalize)]
+pub struct FilterIter {
+    inner: Gc<GcCell<Box<dyn ValueIterator>>>,
+    f: GcValue,
+}
+
+impl FilterIter {
+    pub fn new(inner: Gc<GcCell<Box<dyn ValueIterator>>>, f: GcValue) -> Self {
+        Self { inner, f }
+    }
+}
+
+impl ValueIterator for FilterIter {
+    fn next(&mut self, context: &mut Context) -> Result<Option<GcValue>, Error> {
+        loop {
+            let Some(item) = advance(&self.inner, context)? else {
+                return Ok(None);
+            };
+
+            let keep = context.call_value(&self.f, std::slice::from_ref(&item))?;
+            let keep = matches!(*keep.borrow(), Value::Bool(true));
+
+            if keep {
+                return Ok(Some(item));
+            }
+        }
+    }
+}
+
+/// The items left in a `for` loop, as pairs of each item's index or key and the item itself.
+#[derive(Trace, Finalize)]
+pub(crate) enum ForItems {
+    /// The entries of an object without a `next` method
+    Entries(VecDeque<(GcValue, GcValue)>),
+    Iter {
+        iter: Gc<GcCell<Box<dyn ValueIterator>>>,
+        index: usize,
+    },
+}
+
+impl ForItems {
+    pub(crate) fn next(
+        &mut self,
+        context: &mut Context,
+    ) -> Result<Option<(GcValue, GcValue)>, Error> {
+        match self {
+            ForItems::Entries(entries) => Ok(entries.pop_front()),
+            ForItems::Iter { iter, index } => {
+                let item = advance(iter, context)?;
+                let key = Value::Number(*index as f64).into_gc();
+                *index += 1;
+
+                Ok(item.map(|item| (key, item)))
+            }
+        }
+    }
+
+    /// Whether a loop with a single identifier binds it to the key, rather than the item.
+    pub(crate) fn binds_key(&self) -> bool {
+        matches!(self, ForItems::Entries(_))
+    }
+}
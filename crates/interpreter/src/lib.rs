@@ -1,14 +1,25 @@
 #![doc = include_str!("../README.md")]
 
+// `gc`'s `Trace` and `Finalize` derives put their impls inside of named constants, which trips
+// `non_local_definitions`. The lint can't be allowed on the derived items themselves, so it is allowed
+// on just the modules that derive them.
+#[allow(non_local_definitions)]
 mod callable;
 mod context;
 mod error;
-mod stack;
+#[allow(non_local_definitions)]
+mod generator;
+#[allow(non_local_definitions)]
+mod iterator;
+#[allow(non_local_definitions)]
+mod scope;
 mod stdlib;
+#[allow(non_local_definitions)]
 mod value;
 
 pub use callable::{Callable, InterpretedFn, NativeFn};
 pub use context::{BlockExit, Context};
-pub use error::Error;
+pub use error::{Error, RuntimeError, TraceFrame};
 pub use gc::GcCell;
+pub use iterator::{advance, iter_value, ValueIterator};
 pub use value::{GcValue, ShallowValue, Value};
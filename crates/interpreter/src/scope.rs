@@ -0,0 +1,58 @@
+use gc::{Finalize, Gc, GcCell, Trace};
+
+use crate::GcValue;
+
+/// The variables declared in a single block, like the body of a function or loop.
+///
+/// Scopes live on the heap, so a function can keep the scope it was defined in alive
+/// after that block has been exited.
+#[derive(Debug, Trace, Finalize)]
+pub struct Scope {
+    values: GcCell<Vec<(String, GcValue)>>,
+    parent: Option<Gc<Scope>>,
+}
+
+impl Scope {
+    pub fn new(parent: Option<Gc<Scope>>) -> Gc<Self> {
+        Gc::new(Self {
+            values: GcCell::new(Vec::new()),
+            parent,
+        })
+    }
+
+    pub fn push_value(&self, ident: String, value: GcValue) {
+        self.values.borrow_mut().push((ident, value))
+    }
+
+    /// Searches this scope, and then each of its parents, for a variable.
+    pub fn find_with_ident(&self, ident: &str) -> Option<GcValue> {
+        let found = self
+            .values
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|(i, value)| (i == ident).then(|| value.clone()));
+
+        found.or_else(|| self.parent.as_ref()?.find_with_ident(ident))
+    }
+
+    /// Like [`Scope::find_with_ident`], but stops before searching `root`.
+    pub fn find_with_ident_below(&self, ident: &str, root: &Gc<Scope>) -> Option<GcValue> {
+        let found = self
+            .values
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|(i, value)| (i == ident).then(|| value.clone()));
+
+        found.or_else(|| {
+            let parent = self.parent.as_ref()?;
+
+            if Gc::ptr_eq(parent, root) {
+                None
+            } else {
+                parent.find_with_ident_below(ident, root)
+            }
+        })
+    }
+}
@@ -1,4 +1,6 @@
+use crate::iterator::{advance, iter_value, FilterIter, MapIter, RangeIter};
 use crate::{Context, Error, GcValue, NativeFn, ShallowValue, Value};
+use std::collections::{HashMap, VecDeque};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn add_stdlib(context: &mut Context) {
@@ -13,6 +15,20 @@ pub fn add_stdlib(context: &mut Context) {
     context.add_native_fn("shift".to_string(), NativeFn(shift));
 
     context.add_native_fn("len".to_string(), NativeFn(len));
+
+    context.add_native_fn("identical".to_string(), NativeFn(identical));
+
+    context.add_native_fn("iter".to_string(), NativeFn(iter));
+
+    context.add_native_fn("next".to_string(), NativeFn(next));
+
+    context.add_native_fn("range".to_string(), NativeFn(range));
+
+    context.add_native_fn("map".to_string(), NativeFn(map));
+
+    context.add_native_fn("filter".to_string(), NativeFn(filter));
+
+    context.add_native_fn("collect".to_string(), NativeFn(collect));
 }
 
 fn timestamp(_ctx: &mut Context, _args: &[GcValue]) -> Result<GcValue, Error> {
@@ -112,3 +128,107 @@ fn len(_ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
 
     Ok(Value::Number(len as f64).into_gc())
 }
+
+/// Checks whether two arrays, objects or functions are the same instance, rather than just equal.
+/// Other values are compared with `==`, since they are always passed around as copies.
+fn identical(_ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.len() < 2 {
+        return Err(Error::IncorrectArgumentCount(2, args.len()));
+    }
+
+    let (a, b) = (&args[0], &args[1]);
+
+    let res = match (&*a.borrow(), &*b.borrow()) {
+        (Value::Array(_), Value::Array(_)) | (Value::Object(_), Value::Object(_)) => a.ptr_eq(b),
+        (a, b) => matches!(a.equals(b)?, Value::Bool(true)),
+    };
+
+    Ok(Value::Bool(res).into_gc())
+}
+
+fn iter(_ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.is_empty() {
+        return Err(Error::IncorrectArgumentCount(1, args.len()));
+    }
+
+    Ok(Value::Iterator(iter_value(&args[0])?).into_gc())
+}
+
+/// Advances an iterator, returning an object in the same shape as the iterator protocol, like `{ done: false, value: 1 }`.
+fn next(ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.is_empty() {
+        return Err(Error::IncorrectArgumentCount(1, args.len()));
+    }
+
+    let iter = match &*args[0].borrow() {
+        Value::Iterator(iter) => iter.clone(),
+        other => return Err(Error::TypeError(ShallowValue::Iterator, other.as_shallow())),
+    };
+
+    let item = advance(&iter, ctx)?;
+
+    let mut res = HashMap::new();
+    res.insert("done".to_string(), Value::Bool(item.is_none()).into_gc());
+    res.insert(
+        "value".to_string(),
+        item.unwrap_or_else(|| Value::Null.into_gc()),
+    );
+
+    Ok(Value::Object(res).into_gc())
+}
+
+/// Lazily counts up from the first argument, stopping before the second.
+fn range(_ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.len() < 2 {
+        return Err(Error::IncorrectArgumentCount(2, args.len()));
+    }
+
+    let mut bounds = [0.; 2];
+
+    for (bound, arg) in bounds.iter_mut().zip(args) {
+        match &*arg.borrow() {
+            Value::Number(n) => *bound = *n,
+            other => return Err(Error::TypeError(ShallowValue::Number, other.as_shallow())),
+        }
+    }
+
+    let iter = RangeIter::new(bounds[0], bounds[1]);
+
+    Ok(Value::iterator(iter).into_gc())
+}
+
+fn map(_ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.len() < 2 {
+        return Err(Error::IncorrectArgumentCount(2, args.len()));
+    }
+
+    let iter = MapIter::new(iter_value(&args[0])?, args[1].clone());
+
+    Ok(Value::iterator(iter).into_gc())
+}
+
+fn filter(_ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.len() < 2 {
+        return Err(Error::IncorrectArgumentCount(2, args.len()));
+    }
+
+    let iter = FilterIter::new(iter_value(&args[0])?, args[1].clone());
+
+    Ok(Value::iterator(iter).into_gc())
+}
+
+/// Runs an iterator to the end, putting each of its items into an array.
+fn collect(ctx: &mut Context, args: &[GcValue]) -> Result<GcValue, Error> {
+    if args.is_empty() {
+        return Err(Error::IncorrectArgumentCount(1, args.len()));
+    }
+
+    let iter = iter_value(&args[0])?;
+    let mut items = VecDeque::new();
+
+    while let Some(item) = advance(&iter, ctx)? {
+        items.push_back(item);
+    }
+
+    Ok(Value::Array(items).into_gc())
+}
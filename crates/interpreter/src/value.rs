@@ -1,13 +1,12 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Display;
 use std::ops::Deref;
-use std::rc::Rc;
 
-use ast::BinaryOpKind;
+use ast::{BinaryOpKind, UnaryOpKind};
 use gc::{Finalize, Gc, GcCell, GcCellRef, GcCellRefMut, Trace};
 
 use crate::error::Error;
-use crate::Callable;
+use crate::{Callable, ValueIterator};
 
 #[derive(Clone, Trace, Finalize)]
 pub enum Value {
@@ -16,7 +15,8 @@ pub enum Value {
     Bool(bool),
     Array(VecDeque<GcValue>),
     Object(HashMap<String, GcValue>),
-    Callable(Rc<GcCell<dyn Callable>>),
+    Callable(Gc<GcCell<Box<dyn Callable>>>),
+    Iterator(Gc<GcCell<Box<dyn ValueIterator>>>),
     Null,
 }
 
@@ -29,6 +29,7 @@ impl Value {
             Value::Array(_) => ShallowValue::Array,
             Value::Object(_) => ShallowValue::Object,
             Value::Callable(_) => ShallowValue::Callable,
+            Value::Iterator(_) => ShallowValue::Iterator,
             Value::Null => ShallowValue::Null,
         }
     }
@@ -42,6 +43,7 @@ pub enum ShallowValue {
     Array,
     Object,
     Callable,
+    Iterator,
     Null,
 }
 
@@ -54,6 +56,7 @@ impl Display for ShallowValue {
             ShallowValue::Array => "Array",
             ShallowValue::Object => "Object",
             ShallowValue::Callable => "Callable",
+            ShallowValue::Iterator => "Iterator",
             ShallowValue::Null => "Null",
         };
 
@@ -81,6 +84,16 @@ impl GcValue {
         self.inner.borrow_mut()
     }
 
+    /// Whether both point to the same value on the heap.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Gc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Where the value is on the heap, for telling values apart by identity.
+    fn as_ptr(&self) -> *const GcCell<Value> {
+        &*self.inner
+    }
+
     /// For when you want to pass a value either by referance or by value depending on it's type.
     pub fn shallow_copy(&self) -> Self {
         match &*self.borrow() {
@@ -137,7 +150,8 @@ impl Display for GcValue {
                 write!(f, "{s}")
             }
             Value::Callable(_) => write!(f, "Function"),
-            Value::Null => write!(f, "Null"),
+            Value::Iterator(_) => write!(f, "Iterator"),
+            Value::Null => write!(f, "null"),
         }
     }
 }
@@ -152,6 +166,14 @@ impl Value {
     pub fn into_gc(self) -> GcValue {
         GcValue::new(self)
     }
+
+    pub fn callable(callable: impl Callable + 'static) -> Self {
+        Value::Callable(Gc::new(GcCell::new(Box::new(callable))))
+    }
+
+    pub fn iterator(iter: impl ValueIterator + 'static) -> Self {
+        Value::Iterator(Gc::new(GcCell::new(Box::new(iter))))
+    }
 }
 
 macro_rules! impl_op {
@@ -180,6 +202,42 @@ macro_rules! impl_op {
     };
 }
 
+/// Implements an operation that is only defined on whole numbers, such as the
+/// bitwise operators.
+macro_rules! impl_integer_op {
+    ($op_kind:ident, $op:expr) => {
+        paste::paste! {
+            pub fn [<$op_kind:snake>](&self, other: &Self) -> Result<Self, Error> {
+                match (self, other) {
+                    (Value::Number(a), Value::Number(b)) => {
+                        let v = $op;
+
+                        v(as_integer(*a)?, as_integer(*b)?).map(|n: i64| Value::Number(n as f64))
+                    }
+                    (Value::Number(_), _) => Err(Error::TypeError(ShallowValue::Number, other.as_shallow())),
+                    _ => Err(Error::InvalidBinaryOpArgs(self.as_shallow(), other.as_shallow(), BinaryOpKind::$op_kind)),
+                }
+            }
+        }
+    };
+}
+
+fn as_integer(n: f64) -> Result<i64, Error> {
+    if n.fract() == 0. && n.is_finite() {
+        Ok(n as i64)
+    } else {
+        Err(Error::ExpectedInteger(n))
+    }
+}
+
+fn as_shift_amount(n: i64) -> Result<u32, Error> {
+    if (0..64).contains(&n) {
+        Ok(n as u32)
+    } else {
+        Err(Error::ShiftOutOfRange(n as f64))
+    }
+}
+
 impl Value {
     impl_op!(Add,
         Number => |a, b| Value::Number(a + b),
@@ -203,8 +261,22 @@ impl Value {
         Number => |a, b| Value::Number(a / b)
     );
 
+    impl_op!(IntegerDivide,
+        Number => |a: &f64, b: &f64| Value::Number((a / b).floor())
+    );
+
+    impl_op!(Remainder,
+        Number => |a, b| Value::Number(a % b)
+    );
+
     impl_op!(GreaterThan,
-        Number => |a, b| Value::Bool(a > b)
+        Number => |a, b| Value::Bool(a > b),
+        String => |a: &str, b: &str| Value::Bool(a > b)
+    );
+
+    impl_op!(GreaterThanOrEquals,
+        Number => |a, b| Value::Bool(a >= b),
+        String => |a: &str, b: &str| Value::Bool(a >= b)
     );
 
     impl_op!(Pow,
@@ -212,25 +284,126 @@ impl Value {
     );
 
     impl_op!(LessThan,
-        Number => |a, b| Value::Bool(a < b)
+        Number => |a, b| Value::Bool(a < b),
+        String => |a: &str, b: &str| Value::Bool(a < b)
+    );
+
+    impl_op!(LessThanOrEquals,
+        Number => |a, b| Value::Bool(a <= b),
+        String => |a: &str, b: &str| Value::Bool(a <= b)
+    );
+
+    /// Values of different types are never equal, so comparing them yields `false` rather than an error.
+    ///
+    /// Arrays and objects are compared structurally.
+    pub fn equals(&self, other: &Self) -> Result<Self, Error> {
+        Ok(Value::Bool(
+            self.structurally_equals(other, &mut HashSet::new()),
+        ))
+    }
+
+    /// `visited` holds every pair of containers that has been compared so far, and is assumed to be equal.
+    /// If the assumption is wrong, some other part of the comparison fails and the whole comparison is `false`
+    /// anyway. This stops cycles from recursing forever, and shared items from being compared more than once.
+    fn structurally_equals(
+        &self,
+        other: &Self,
+        visited: &mut HashSet<(*const GcCell<Value>, *const GcCell<Value>)>,
+    ) -> bool {
+        let mut items_equal = |a: &GcValue, b: &GcValue| {
+            if a.ptr_eq(b) || !visited.insert((a.as_ptr(), b.as_ptr())) {
+                return true;
+            }
+
+            a.borrow().structurally_equals(&b.borrow(), visited)
+        };
+
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Callable(a), Value::Callable(b)) => Gc::ptr_eq(a, b),
+            (Value::Iterator(a), Value::Iterator(b)) => Gc::ptr_eq(a, b),
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| items_equal(a, b))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, a)| match b.get(key) {
+                        Some(b) => items_equal(a, b),
+                        None => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    pub fn not_equals(&self, other: &Self) -> Result<Self, Error> {
+        self.equals(other)?.not()
+    }
+
+    impl_integer_op!(BitAnd, |a, b| Ok(a & b));
+
+    impl_integer_op!(BitOr, |a, b| Ok(a | b));
+
+    impl_integer_op!(BitXor, |a, b| Ok(a ^ b));
+
+    impl_integer_op!(ShiftLeft, |a: i64, b| Ok(a << as_shift_amount(b)?));
+
+    impl_integer_op!(ShiftRight, |a: i64, b| Ok(a >> as_shift_amount(b)?));
+
+    impl_op!(And,
+        Bool => |a: &bool, b: &bool| Value::Bool(*a && *b)
     );
 
-    impl_op!(Equals,
-        Number => |a, b| Value::Bool(a == b),
-        Bool => |a, b| Value::Bool(a == b),
-        String => |a:&str, b:&str| Value::Bool(a.eq(b))
+    impl_op!(Or,
+        Bool => |a: &bool, b: &bool| Value::Bool(*a || *b)
     );
 
+    pub fn negate(&self) -> Result<Self, Error> {
+        match self {
+            Value::Number(n) => Ok(Value::Number(-n)),
+            _ => Err(Error::TypeError(ShallowValue::Number, self.as_shallow())),
+        }
+    }
+
+    pub fn not(&self) -> Result<Self, Error> {
+        match self {
+            Value::Bool(b) => Ok(Value::Bool(!b)),
+            _ => Err(Error::TypeError(ShallowValue::Bool, self.as_shallow())),
+        }
+    }
+
+    pub fn run_unary_op(&self, op: UnaryOpKind) -> Result<Self, Error> {
+        match op {
+            UnaryOpKind::Negate => self.negate(),
+            UnaryOpKind::Not => self.not(),
+        }
+    }
+
     pub fn run_binary_op(&self, other: &Self, op: BinaryOpKind) -> Result<Self, Error> {
         match op {
             ast::BinaryOpKind::Add => self.add(other),
             ast::BinaryOpKind::Subtract => self.subtract(other),
             ast::BinaryOpKind::Multiply => self.multiply(other),
             ast::BinaryOpKind::Divide => self.divide(other),
+            ast::BinaryOpKind::IntegerDivide => self.integer_divide(other),
+            ast::BinaryOpKind::Remainder => self.remainder(other),
             ast::BinaryOpKind::GreaterThan => self.greater_than(other),
+            ast::BinaryOpKind::GreaterThanOrEquals => self.greater_than_or_equals(other),
             ast::BinaryOpKind::LessThan => self.less_than(other),
+            ast::BinaryOpKind::LessThanOrEquals => self.less_than_or_equals(other),
             ast::BinaryOpKind::Pow => self.pow(other),
             ast::BinaryOpKind::Equals => self.equals(other),
+            ast::BinaryOpKind::NotEquals => self.not_equals(other),
+            ast::BinaryOpKind::BitAnd => self.bit_and(other),
+            ast::BinaryOpKind::BitOr => self.bit_or(other),
+            ast::BinaryOpKind::BitXor => self.bit_xor(other),
+            ast::BinaryOpKind::ShiftLeft => self.shift_left(other),
+            ast::BinaryOpKind::ShiftRight => self.shift_right(other),
+            ast::BinaryOpKind::And => self.and(other),
+            ast::BinaryOpKind::Or => self.or(other),
         }
     }
 }
@@ -240,9 +413,3 @@ impl From<String> for Value {
         Self::String(v)
     }
 }
-
-impl From<Rc<GcCell<dyn Callable>>> for Value {
-    fn from(value: Rc<GcCell<dyn Callable>>) -> Self {
-        Value::Callable(value)
-    }
-}
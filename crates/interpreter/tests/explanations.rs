@@ -0,0 +1,72 @@
+use diagnostics::Code;
+use interpreter::Context;
+
+/// The ```thrax code blocks of an explanation, in order.
+fn examples(explanation: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in explanation.lines() {
+        match (&mut current, line.trim_end()) {
+            (None, "```thrax") => current = Some(String::new()),
+            (Some(example), "```") => {
+                examples.push(std::mem::take(example));
+                current = None;
+            }
+            (Some(example), line) => {
+                example.push_str(line);
+                example.push('\n');
+            }
+            (None, _) => {}
+        }
+    }
+
+    examples
+}
+
+/// Parses and runs a program, returning the code of the first error it runs into.
+fn error_code(source: &str) -> Option<Code> {
+    let program = match parser::parse_string(source) {
+        Ok(program) => program,
+        Err(err) => return Some(err.to_diagnostic().code),
+    };
+
+    let mut context = Context::new();
+    context.add_stdlib();
+
+    context
+        .run_program(&program)
+        .err()
+        .map(|err| err.error.code())
+}
+
+#[test]
+fn examples_produce_their_own_code() {
+    for code in Code::ALL {
+        let examples = examples(code.explanation());
+
+        // Any examples after the first show how to fix it.
+        let Some((example, fixes)) = examples.split_first() else {
+            // Statements that failed to parse can only be run by using the parser's recovery directly.
+            assert!(
+                *code == Code::SYNTAX_ERROR,
+                "{code} should have an example."
+            );
+            continue;
+        };
+
+        assert_eq!(
+            error_code(example).map(Code::id),
+            Some(code.id()),
+            "The example of {code} should produce it."
+        );
+
+        for fix in fixes {
+            assert_eq!(
+                error_code(fix).map(Code::id),
+                None,
+                "The fix for {code} should run."
+            );
+        }
+    }
+}
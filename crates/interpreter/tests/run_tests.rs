@@ -1,6 +1,21 @@
-use interpreter::{BlockExit, Context};
+use diagnostics::{Code, LabelStyle};
+use interpreter::{BlockExit, Context, Error, ShallowValue};
 
 macro_rules! create_test {
+    ($filename:ident, Err($e:pat)) => {
+       paste::paste! {
+           #[test]
+           fn [<runs_$filename>](){
+                let source = include_str!(concat!("./tests_sources/", stringify!($filename), ".th"));
+
+                let ast = parser::parse_string(&source).unwrap();
+                let mut context = Context::new();
+                context.add_stdlib();
+
+                assert!(matches!(context.eval_program(&ast), Err($e)));
+           }
+       }
+    };
     ($filename:ident, $e:pat) => {
        paste::paste! {
            #[test]
@@ -15,6 +30,26 @@ macro_rules! create_test {
            }
        }
     };
+    ($filename:ident, $e:pat, $expected:literal) => {
+       paste::paste! {
+           #[test]
+           fn [<runs_$filename>](){
+                let source = include_str!(concat!("./tests_sources/", stringify!($filename), ".th"));
+
+                let ast = parser::parse_string(&source).unwrap();
+                let mut context = Context::new();
+                context.add_stdlib();
+
+                let res = context.eval_program(&ast).unwrap();
+                assert!(matches!(res, $e));
+
+                let BlockExit::Returned(Some(value)) = res else {
+                    panic!("The test program did not return a value.");
+                };
+                assert_eq!(value.to_string(), $expected);
+           }
+       }
+    };
 }
 
 create_test!(while_loop, BlockExit::Completed);
@@ -28,3 +63,309 @@ create_test!(break_continue, BlockExit::Returned(Some(_)));
 create_test!(stack, BlockExit::Returned(Some(_)));
 create_test!(queue, BlockExit::Returned(Some(_)));
 create_test!(primes, BlockExit::Returned(Some(_)));
+create_test!(checked_primes, BlockExit::Returned(Some(_)), "[62, 293]");
+create_test!(
+    precedence,
+    BlockExit::Returned(Some(_)),
+    "[3, 512, 21, 2, 2, true]"
+);
+create_test!(
+    strings,
+    BlockExit::Returned(Some(_)),
+    "[She said \"hi\", C:\\new\\table, a \"raw\" string, Report:\n\t\"quoted\" items, \u{1F600}, 2]"
+);
+create_test!(
+    interpolation,
+    BlockExit::Returned(Some(_)),
+    "[Hello Ada, you have 3 items, outer inner 2, cost: ${price}, [1, 2, 3]]"
+);
+create_test!(
+    unary,
+    BlockExit::Returned(Some(_)),
+    "[-5, 5, true, true, -4, 0.5, -3, -12]"
+);
+
+#[test]
+fn unary_ops_reject_wrong_types() {
+    let mut context = Context::new();
+
+    let negate = parser::parse_string("-\"text\";").unwrap();
+    assert!(matches!(
+        context.eval_program(&negate),
+        Err(Error::TypeError(ShallowValue::Number, ShallowValue::String))
+    ));
+
+    let not = parser::parse_string("!1;").unwrap();
+    assert!(matches!(
+        context.eval_program(&not),
+        Err(Error::TypeError(ShallowValue::Bool, ShallowValue::Number))
+    ));
+}
+create_test!(
+    if_number,
+    Err(Error::TypeError(ShallowValue::Bool, ShallowValue::Number))
+);
+create_test!(
+    while_string,
+    Err(Error::TypeError(ShallowValue::Bool, ShallowValue::String))
+);
+create_test!(
+    logical,
+    BlockExit::Returned(Some(_)),
+    "[2, -1, false, true, 0, true]"
+);
+create_test!(
+    operators,
+    BlockExit::Returned(Some(_)),
+    "[1, -1, 3, -4, 2, 7, 5, 16, -4, 8, 3, 3, true, false, true, false, true]"
+);
+create_test!(
+    comparisons,
+    BlockExit::Returned(Some(_)),
+    "[[Zebra, apple, fig, pear], true, true, true, false, true, true, false, false]"
+);
+create_test!(
+    deep_equality,
+    BlockExit::Returned(Some(_)),
+    "[true, true, true, false, false, true, false, false, true, true, true]"
+);
+create_test!(
+    shared_equality,
+    BlockExit::Returned(Some(_)),
+    "[true, true, true, true]"
+);
+create_test!(
+    operand_side_effects,
+    BlockExit::Returned(Some(_)),
+    "[2, 2, 20]"
+);
+create_test!(
+    null,
+    BlockExit::Returned(Some(_)),
+    "[1, true, true, true, false, 2, [null, null], null]"
+);
+create_test!(
+    anonymous_fns,
+    BlockExit::Returned(Some(_)),
+    "[[2, 4, 6], [11, 12, 13], [odd, even], 81, clicked, Function]"
+);
+create_test!(
+    closures,
+    BlockExit::Returned(Some(_)),
+    "[3, 2, 6, true, true, 0, 20, 3]"
+);
+create_test!(
+    callees,
+    BlockExit::Returned(Some(_)),
+    "[[hi!, hihi], a!a!, 12, 2]"
+);
+create_test!(
+    methods,
+    BlockExit::Returned(Some(_)),
+    "[Grace, 35, [0, 1, 2], 3, 3, hey!, 3]"
+);
+create_test!(
+    object_insert,
+    BlockExit::Returned(Some(_)),
+    "[36, 42, 2, 12, blue, [1, 5]]"
+);
+create_test!(
+    for_loops,
+    BlockExit::Returned(Some(_)),
+    "[10, [0h, 1é, 4o], [a=1, b=2, c=3], [x, y], [0, 1, 4, 9], 8, [1, 2], [2, 3, 4]]"
+);
+create_test!(huge_ranges, BlockExit::Returned(Some(_)), "[1, 1, 3, 4]");
+create_test!(
+    iterators,
+    BlockExit::Returned(Some(_)),
+    "[[3, 2, 1], [0, 9, 36], 144, [0, 1, 2, 3, 4, a, b, 2, 1], 6, [30, 20, 10], [a, b, true, null], Iterator]"
+);
+create_test!(
+    generators,
+    BlockExit::Returned(Some(_)),
+    "[[0, 2, 4, 6], [lazy, data, pipelines], [10, 11, true], [0, 1, [started]], [0, 1, 4, 9], []]"
+);
+
+#[test]
+fn bitwise_ops_require_integers() {
+    let mut context = Context::new();
+
+    let fractional = parser::parse_string("1.5 & 1;").unwrap();
+    assert!(matches!(
+        context.eval_program(&fractional),
+        Err(Error::ExpectedInteger(n)) if n == 1.5
+    ));
+
+    let shift = parser::parse_string("1 << 64;").unwrap();
+    assert!(matches!(
+        context.eval_program(&shift),
+        Err(Error::ShiftOutOfRange(n)) if n == 64.
+    ));
+}
+
+#[test]
+fn missing_methods_name_the_receiver() {
+    let mut context = Context::new();
+
+    let program = parser::parse_string("let x = 5; x.nope();").unwrap();
+    assert!(matches!(
+        context.eval_program(&program),
+        Err(Error::UndefinedMethod(ShallowValue::Number, method)) if method == "nope"
+    ));
+}
+
+#[test]
+fn invalid_assignments_are_rejected() {
+    let mut context = Context::new();
+
+    let out_of_bounds = parser::parse_string("let arr = [1, 2]; arr[2] = 3;").unwrap();
+    assert!(matches!(
+        context.eval_program(&out_of_bounds),
+        Err(Error::AssignmentOutOfBounds(index, 2)) if index == 2.
+    ));
+
+    let missing_key = parser::parse_string("let obj = {}; obj.count += 1;").unwrap();
+    assert!(matches!(
+        context.eval_program(&missing_key),
+        Err(Error::ObjectMissingKey(key)) if key == "count"
+    ));
+
+    let string_index = parser::parse_string("let s = \"abc\"; s[0] = \"b\";").unwrap();
+    assert!(matches!(
+        context.eval_program(&string_index),
+        Err(Error::CannotAssignIndex(ShallowValue::String))
+    ));
+
+    let call = parser::parse_string("fn f() {} f() = 1;").unwrap();
+    assert!(matches!(
+        context.eval_program(&call),
+        Err(Error::InvalidAssignmentTarget)
+    ));
+}
+
+#[test]
+fn yield_is_only_allowed_in_generators() {
+    let mut context = Context::new();
+
+    let program = parser::parse_string("fn f() { yield 1; } f();").unwrap();
+    assert!(matches!(
+        context.eval_program(&program),
+        Err(Error::YieldOutsideGenerator)
+    ));
+}
+
+#[test]
+fn undeclared_variables_are_reported() {
+    let mut context = Context::new();
+
+    let read = parser::parse_string("fn area() { return width * 2; } area();").unwrap();
+    let err = context.run_program(&read).unwrap_err();
+    assert!(matches!(&err.error, Error::UndefinedVariable(ident) if ident == "width"));
+    assert_eq!(err.error.code(), Code::UNDEFINED_VARIABLE);
+
+    let assignment = parser::parse_string("total = 5;").unwrap();
+    let err = context.run_program(&assignment).unwrap_err();
+    assert!(matches!(&err.error, Error::Undeclared(ident) if ident == "total"));
+    assert_eq!(err.error.code(), Code::UNDECLARED);
+}
+
+#[test]
+fn iterators_cannot_advance_themselves() {
+    let programs = [
+        "let it = null; fn* g() { yield 1; for (x in it) { yield x; } } it = g(); for (x in it) {}",
+        "let it = null; fn* g() { yield next(it); } it = g(); next(it);",
+        "let it = null; fn* g() { yield [...it]; } it = g(); collect(it);",
+        "let it = null; fn* g() { yield collect(it); } it = map(g(), fn(x) => x); collect(it);",
+    ];
+
+    for source in programs {
+        let program = parser::parse_string(source).unwrap();
+        let mut context = Context::new();
+        context.add_stdlib();
+
+        assert!(
+            matches!(
+                context.eval_program(&program),
+                Err(Error::IteratorAlreadyRunning)
+            ),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn runtime_errors_point_to_where_they_happened() {
+    let source = "fn get(arr, i) {\n  return arr[i];\n}\n\nlet apply = fn(f) => f();\napply(fn() => get([1, 2], 5));";
+
+    let program = parser::parse_string(source).unwrap();
+    let err = Context::new().run_program(&program).unwrap_err();
+
+    assert!(matches!(err.error, Error::IndexOutOfBounds(5)));
+    assert_eq!(&source[err.span.start..err.span.end], "arr[i]");
+
+    let backtrace: Vec<_> = err
+        .backtrace
+        .iter()
+        .map(|frame| {
+            (
+                frame.name.as_deref(),
+                &source[frame.call_site.start..frame.call_site.end],
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        backtrace,
+        vec![
+            (Some("apply"), "apply(fn() => get([1, 2], 5))"),
+            (Some("f"), "f()"),
+            (Some("get"), "get([1, 2], 5)"),
+        ]
+    );
+}
+
+#[test]
+fn statement_errors_point_to_the_statement() {
+    let source = "let a = 1;\nlet a = 2;";
+
+    let program = parser::parse_string(source).unwrap();
+    let err = Context::new().run_program(&program).unwrap_err();
+
+    assert!(matches!(err.error, Error::Redeclaration(ident) if ident == "a"));
+    assert_eq!(&source[err.span.start..err.span.end], "let a = 2;");
+    assert!(err.backtrace.is_empty());
+}
+
+#[test]
+fn runtime_errors_become_diagnostics() {
+    let source = "fn f() {\n  yield 1;\n}\nf();";
+
+    let program = parser::parse_string(source).unwrap();
+    let diagnostic = Context::new()
+        .run_program(&program)
+        .unwrap_err()
+        .to_diagnostic();
+
+    assert_eq!(diagnostic.code, Code::YIELD_OUTSIDE_GENERATOR);
+    assert!(diagnostic.help.unwrap().contains("fn*"));
+
+    let labels: Vec<_> = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            (
+                label.style,
+                &source[label.span.start..label.span.end],
+                label.message.as_str(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        labels,
+        vec![
+            (LabelStyle::Primary, "yield 1;", ""),
+            (LabelStyle::Secondary, "f()", "`f` called here"),
+        ]
+    );
+}
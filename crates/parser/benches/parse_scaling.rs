@@ -0,0 +1,86 @@
+//! Measures how long [`parser::lex_string`] and [`parser::parse_tokens`] take on generated programs
+//! of increasing size.
+//!
+//! Run with `cargo bench -p parser`.
+//! If both are linear, the time spent per token should stay roughly constant as the programs grow.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use parser::{lex_string, parse_tokens};
+
+const LINE_COUNTS: [usize; 4] = [10_000, 20_000, 40_000, 80_000];
+const RUNS: u32 = 5;
+
+/// How deep the nested array literals get.
+const NESTING_DEPTH: usize = 50;
+
+fn main() {
+    println!(
+        "{:>8} {:>10} {:>10} {:>12} {:>12} {:>12} {:>12}",
+        "lines", "tokens", "per line", "lex", "per token", "parse", "per token"
+    );
+
+    for line_count in LINE_COUNTS {
+        let source = generate_lines(line_count).join("\n");
+
+        let mut lex_total = Duration::ZERO;
+        let mut parse_total = Duration::ZERO;
+        let mut token_count = 0;
+
+        for _ in 0..RUNS {
+            let start = Instant::now();
+            let tokens = lex_string(black_box(&source)).expect("Generated program should lex.");
+            lex_total += start.elapsed();
+
+            let start = Instant::now();
+            let program =
+                parse_tokens(black_box(&tokens)).expect("Generated program should parse.");
+            parse_total += start.elapsed();
+
+            token_count = tokens.len();
+            black_box(program);
+        }
+
+        let (lex_average, parse_average) = (lex_total / RUNS, parse_total / RUNS);
+
+        println!(
+            "{:>8} {:>10} {:>10.2} {:>12.2?} {:>10.2}ns {:>12.2?} {:>10.2}ns",
+            line_count,
+            token_count,
+            token_count as f64 / line_count as f64,
+            lex_average,
+            lex_average.as_nanos() as f64 / token_count as f64,
+            parse_average,
+            parse_average.as_nanos() as f64 / token_count as f64
+        );
+    }
+}
+
+fn generate_lines(line_count: usize) -> Vec<String> {
+    let mut lines = Vec::with_capacity(line_count);
+    // Counted separately from the lines, so the mix of statements doesn't depend on how many lines each one takes up
+    let mut statement = 0;
+
+    while lines.len() < line_count {
+        let i = lines.len();
+
+        match statement % 5 {
+            0 => lines.push(format!("let v{i} = {i} + {i} * 2 - ({i} / 3) ** 2 ** 1;")),
+            1 => lines.push(format!(
+                "v{i} += f(v{i}, [1, 2, [3, 4]], {{ a: 1, b: [5] }});"
+            )),
+            2 => lines.push(format!("if (v{i} > 3) {{ v{i} = v{i}[0][1]; }} else {{")),
+            3 => lines.push("while (false) { break; } }".to_string()),
+            _ => lines.push(format!(
+                "let n{i} = {}1 + 2{};",
+                "[".repeat(NESTING_DEPTH),
+                "]".repeat(NESTING_DEPTH)
+            )),
+        }
+
+        statement += 1;
+    }
+
+    lines
+}
@@ -1,14 +1,94 @@
 use std::fmt::{Display, Formatter};
 
+use ast::Span;
+use diagnostics::{Code, Diagnostic, Label};
+use is_macro::Is;
+
 #[derive(Debug)]
 pub struct Error {
     pub index: usize,
+    pub kind: ErrorKind,
+}
+
+#[derive(Debug, Is, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// No token can start with the character at the index.
+    UnexpectedCharacter,
+    /// The string starting at the index is never closed.
+    UnterminatedString,
+    /// The `${` at the index is never closed by a matching `}`.
+    UnterminatedInterpolation,
+    /// The escape sequence starting at the index is not one the language supports.
+    InvalidEscape(char),
+    /// The `\u{...}` escape sequence starting at the index is malformed or not a valid character.
+    InvalidUnicodeEscape,
+}
+
+impl Error {
+    pub fn new(index: usize, kind: ErrorKind) -> Self {
+        Self { index, kind }
+    }
+
+    /// Adjusts [`Self::index`] by an index.
+    pub fn offset(mut self, by: usize) -> Self {
+        self.index += by;
+        self
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let label = match self.kind {
+            ErrorKind::UnexpectedCharacter => "unexpected character",
+            ErrorKind::UnterminatedString => "string starts here",
+            ErrorKind::UnterminatedInterpolation => "interpolation starts here",
+            ErrorKind::InvalidEscape(_) | ErrorKind::InvalidUnicodeEscape => "escape sequence",
+        };
+
+        let diagnostic = Diagnostic::error(self.kind.code(), self.kind.to_string())
+            .with_label(Label::primary(Span::new(self.index, self.index), label));
+
+        match self.kind {
+            ErrorKind::UnterminatedString => diagnostic.with_help("Close the string with a `\"`."),
+            ErrorKind::InvalidEscape(_) => diagnostic.with_help("Write a backslash as `\\\\`."),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl ErrorKind {
+    pub fn code(self) -> Code {
+        match self {
+            ErrorKind::UnexpectedCharacter => Code::UNEXPECTED_CHARACTER,
+            ErrorKind::UnterminatedString => Code::UNTERMINATED_STRING,
+            ErrorKind::UnterminatedInterpolation => Code::UNTERMINATED_INTERPOLATION,
+            ErrorKind::InvalidEscape(_) => Code::INVALID_ESCAPE,
+            ErrorKind::InvalidUnicodeEscape => Code::INVALID_UNICODE_ESCAPE,
+        }
+    }
 }
 
 impl std::error::Error for Error {}
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Lexer did not expect character at index {}", self.index)
+        write!(f, "{} at index {}", self.kind, self.index)
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::UnexpectedCharacter => write!(f, "Lexer did not expect character"),
+            ErrorKind::UnterminatedString => write!(f, "String is never closed"),
+            ErrorKind::UnterminatedInterpolation => {
+                write!(f, "Interpolated expression is never closed with a `}}`")
+            }
+            ErrorKind::InvalidEscape(c) => write!(f, "Unknown escape sequence `\\{c}`"),
+            ErrorKind::InvalidUnicodeEscape => {
+                write!(
+                    f,
+                    "Unicode escapes must look like `\\u{{1F600}}` and be a valid character"
+                )
+            }
+        }
     }
 }
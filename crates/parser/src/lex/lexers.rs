@@ -1,5 +1,10 @@
-use super::token::{Span, Token, TokenKind};
-use super::Error;
+use std::str::CharIndices;
+
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use super::string_lexers::lex_string;
+use super::token::{ShallowTokenKind, Span, Token, TokenKind};
+use super::{Error, ErrorKind};
 
 #[derive(Debug)]
 pub struct FoundToken {
@@ -11,39 +16,206 @@ pub struct FoundToken {
 
 /// Lex all tokens, if possible.
 pub fn lex_to_end(source: &[char]) -> Result<Vec<Token>, Error> {
+    let byte_indices = byte_indices(source);
     let mut cursor = 0;
     let mut tokens = Vec::new();
 
-    loop {
-        cursor += lex_ignorables(&source[cursor..]);
+    while let Some(token) = lex_next(source, &mut cursor) {
+        tokens.push(to_byte_indices(token, |index| byte_indices[index])?);
+    }
+
+    Ok(tokens)
+}
+
+/// The byte index each character starts at, followed by the length of the source in bytes.
+///
+/// The lexers work with character indices, but spans are given out in bytes, so they can be used to slice the source.
+fn byte_indices(source: &[char]) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(source.len() + 1);
+    let mut byte_index = 0;
+
+    for c in source {
+        indices.push(byte_index);
+        byte_index += c.len_utf8();
+    }
+
+    indices.push(byte_index);
+
+    indices
+}
+
+/// Converts the character indices in a token or error into byte indices.
+fn to_byte_indices(
+    res: Result<Token, Error>,
+    byte_index: impl Fn(usize) -> usize,
+) -> Result<Token, Error> {
+    match res {
+        Ok(mut token) => {
+            token.map_spans(&|span| Span::new(byte_index(span.start), byte_index(span.end)));
+            Ok(token)
+        }
+        Err(err) => Err(Error::new(byte_index(err.index), err.kind)),
+    }
+}
+
+/// Lex the token after `cursor`, moving `cursor` to the end of it.
+///
+/// Returns `None` once the only thing left in the source is ignorables.
+pub fn lex_next(source: &[char], cursor: &mut usize) -> Option<Result<Token, Error>> {
+    *cursor += lex_ignorables(&source[*cursor..]);
+
+    if *cursor == source.len() {
+        return None;
+    }
+
+    let FoundToken { token, next_index } = match lex_token(&source[*cursor..]) {
+        Ok(Some(found)) => found,
+        Ok(None) => return Some(Err(Error::new(*cursor, ErrorKind::UnexpectedCharacter))),
+        Err(err) => return Some(Err(err.offset(*cursor))),
+    };
+
+    let mut token = Token {
+        span: Span::new(0, next_index),
+        kind: token,
+    };
+    token.offset(*cursor);
+
+    *cursor += next_index;
+
+    Some(Ok(token))
+}
+
+/// How many characters after a token have to be read before the token can be trusted.
+///
+/// Lexers look a little past the end of a token to find where it stops, like checking for an exponent after a
+/// number or a longer piece of punctuation.
+const LOOKAHEAD: usize = 4;
+
+/// How many characters the longest unicode escape takes up, which is `\u{10FFFF}`.
+const LONGEST_UNICODE_ESCAPE: usize = 10;
+
+/// The fewest characters to read from the source at a time.
+const MIN_READ: usize = 64;
+
+/// Lazily lexes tokens from a string, stopping after the first error.
+///
+/// Characters are only read from the source as they are needed for the next token.
+#[derive(Debug, Clone)]
+pub struct TokenIter<'a> {
+    chars: CharIndices<'a>,
+    source_len: usize,
+    /// Characters read from the source, starting at [`Self::start`].
+    buffer: Vec<char>,
+    /// The byte index of each character in [`Self::buffer`].
+    buffer_indices: Vec<usize>,
+    /// Where the characters that haven't been lexed yet start in the buffer.
+    start: usize,
+    errored: bool,
+}
 
-        if cursor == source.len() {
-            return Ok(tokens);
+impl<'a> TokenIter<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.char_indices(),
+            source_len: source.len(),
+            buffer: Vec::new(),
+            buffer_indices: Vec::new(),
+            start: 0,
+            errored: false,
         }
+    }
+
+    /// Reads at least as many characters as are waiting to be lexed, so each retry doubles what the lexers can see.
+    fn read_more(&mut self) {
+        let unlexed = self.buffer.len() - self.start;
+
+        for (index, c) in self.chars.by_ref().take(unlexed.max(MIN_READ)) {
+            self.buffer.push(c);
+            self.buffer_indices.push(index);
+        }
+    }
+
+    /// Drops characters that have already been lexed, once they make up most of the buffer.
+    fn compact(&mut self) {
+        if self.start * 2 >= self.buffer.len() {
+            self.buffer.drain(..self.start);
+            self.buffer_indices.drain(..self.start);
+            self.start = 0;
+        }
+    }
+}
 
-        if let Some(FoundToken { token, next_index }) = lex_token(&source[cursor..]) {
-            tokens.push(Token {
-                span: Span::new(cursor, cursor + next_index),
-                kind: token,
+impl Iterator for TokenIter<'_> {
+    type Item = Result<Token, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+
+        loop {
+            let unlexed = &self.buffer[self.start..];
+            let mut cursor = 0;
+            let res = lex_next(unlexed, &mut cursor);
+
+            let is_complete = match &res {
+                Some(Ok(_)) => cursor + LOOKAHEAD <= unlexed.len(),
+                Some(Err(err)) => !could_be_fixed_by_more(err, unlexed.len()),
+                None => false,
+            };
+
+            if !is_complete && !self.chars.as_str().is_empty() {
+                self.read_more();
+                continue;
+            }
+
+            let res = to_byte_indices(res?, |index| {
+                self.buffer_indices
+                    .get(self.start + index)
+                    .copied()
+                    .unwrap_or(self.source_len)
             });
-            cursor += next_index;
-        } else {
-            return Err(Error { index: cursor });
+
+            self.errored = res.is_err();
+            self.start += cursor;
+            self.compact();
+
+            return Some(res);
         }
     }
 }
 
-/// Runs all lexers over supplied source, returning the first success
-pub fn lex_token(source: &[char]) -> Option<FoundToken> {
-    let lexers = [lex_number, lex_string, lex_keyword, lex_ident];
+/// Whether a lex error might go away if more of the source were read, like a string that is closed later on.
+///
+/// Every other error is already certain, so it can be returned without reading the rest of the source.
+fn could_be_fixed_by_more(err: &Error, read: usize) -> bool {
+    match err.kind {
+        ErrorKind::UnterminatedString | ErrorKind::UnterminatedInterpolation => true,
+        // Cut off partway through a longer token, like the `~` of `~/`
+        ErrorKind::UnexpectedCharacter => err.index + LOOKAHEAD > read,
+        // Cut off partway through the escape, like `\u{1F6`
+        ErrorKind::InvalidUnicodeEscape => err.index + LONGEST_UNICODE_ESCAPE > read,
+        ErrorKind::InvalidEscape(_) => false,
+    }
+}
+
+/// Runs all lexers over supplied source, returning the first success.
+///
+/// Only string literals can fail partway through, every other lexer simply doesn't match.
+pub fn lex_token(source: &[char]) -> Result<Option<FoundToken>, Error> {
+    if let Some(ft) = lex_string(source)? {
+        return Ok(Some(ft));
+    }
+
+    let lexers = [lex_number, lex_punctuation, lex_word];
 
     for lexer in lexers {
         if let Some(ft) = lexer(source) {
-            return Some(ft);
+            return Ok(Some(ft));
         }
     }
 
-    None
+    Ok(None)
 }
 
 /// Find the first token _after_ all ignorables, including whitespace and comments
@@ -83,7 +255,7 @@ pub fn lex_comments(source: &[char]) -> usize {
 
 /// Find the first token _after_ a line comment.
 pub fn lex_line_comment(source: &[char]) -> usize {
-    if let (Some('/'), Some('/')) = (source.get(0), source.get(1)) {
+    if let (Some('/'), Some('/')) = (source.first(), source.get(1)) {
         source
             .iter()
             .enumerate()
@@ -96,7 +268,7 @@ pub fn lex_line_comment(source: &[char]) -> usize {
 
 /// Find the first token _after_ a block comment.
 pub fn lex_block_comment(source: &[char]) -> usize {
-    if let (Some('/'), Some('*')) = (source.get(0), source.get(1)) {
+    if let (Some('/'), Some('*')) = (source.first(), source.get(1)) {
         let mut i = source.iter().enumerate().peekable();
 
         while let Some((index, c)) = i.next() {
@@ -113,71 +285,72 @@ pub fn lex_block_comment(source: &[char]) -> usize {
     }
 }
 
+/// Scans a number of the form `12.5e-3`.
+///
+/// The fraction and exponent are only consumed if they are followed by digits,
+/// so `1.` lexes as the number `1` followed by whatever the `.` turns out to be.
+///
+/// Negative numbers are lexed as a `-` followed by a number, which the parser turns into a negation.
 pub fn lex_number(source: &[char]) -> Option<FoundToken> {
-    if source.is_empty() {
+    let mut cursor = count_digits(source);
+
+    if cursor == 0 {
         return None;
     }
 
-    {
-        let s: String = source.iter().collect();
+    if source.get(cursor) == Some(&'.') {
+        let fraction_digits = count_digits(&source[cursor + 1..]);
 
-        if let Ok(n) = s.parse::<f64>() {
-            return Some(FoundToken {
-                token: TokenKind::Number(n),
-                next_index: source.len(),
-            });
+        if fraction_digits > 0 {
+            cursor += 1 + fraction_digits;
         }
     }
 
-    lex_number(&source[0..source.len() - 1])
-}
-
-fn is_ident_terminator(c: char) -> bool {
-    c.is_whitespace() || "(){},;:[]".contains(c)
-}
+    if let Some('e' | 'E') = source.get(cursor) {
+        let sign = matches!(source.get(cursor + 1), Some('+' | '-')) as usize;
+        let exponent_digits = count_digits(&source[(cursor + 1 + sign).min(source.len())..]);
 
-fn lex_ident(source: &[char]) -> Option<FoundToken> {
-    let mut ident = String::new();
-
-    for (index, c) in source.iter().enumerate() {
-        if is_ident_terminator(*c) {
-            return Some(FoundToken {
-                next_index: index,
-                token: TokenKind::Ident(ident),
-            });
-        } else {
-            ident.push(*c)
+        if exponent_digits > 0 {
+            cursor += 1 + sign + exponent_digits;
         }
     }
 
-    None
+    let text: String = source[..cursor].iter().collect();
+
+    Some(FoundToken {
+        token: TokenKind::Number(text.parse().ok()?),
+        next_index: cursor,
+    })
 }
 
-pub fn lex_string(source: &[char]) -> Option<FoundToken> {
-    if *source.first()? != '\"' {
+/// Counts the ASCII digits at the start of the source.
+fn count_digits(source: &[char]) -> usize {
+    source.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+/// Lexes an identifier following the Unicode XID rules, or a keyword if the whole word is reserved.
+fn lex_word(source: &[char]) -> Option<FoundToken> {
+    let first = *source.first()?;
+
+    if !(is_xid_start(first) || first == '_') {
         return None;
     }
 
-    let mut text = String::new();
+    let len = 1 + source[1..]
+        .iter()
+        .take_while(|c| is_xid_continue(**c))
+        .count();
 
-    for (index, c) in source.iter().enumerate().skip(1) {
-        if *c == '\"' {
-            return Some(FoundToken {
-                next_index: index + 1,
-                token: TokenKind::String(text),
-            });
-        } else {
-            text.push(*c);
-        }
-    }
+    let word: String = source[..len].iter().collect();
 
-    None
+    Some(FoundToken {
+        token: keyword_from_word(&word).unwrap_or(TokenKind::Ident(word)),
+        next_index: len,
+    })
 }
 
 fn lex_characters(source: &[char], cs: &str, token: TokenKind) -> Option<FoundToken> {
-    let sep: Vec<_> = cs.chars().collect();
-
-    if source.get(0..cs.len())? == sep {
+    if source.get(0..cs.len())?.iter().copied().eq(cs.chars()) {
         Some(FoundToken {
             token,
             next_index: cs.len(),
@@ -189,7 +362,7 @@ fn lex_characters(source: &[char], cs: &str, token: TokenKind) -> Option<FoundTo
 
 macro_rules! lex_chars_to {
     ($($text:literal => $res:ident),*) => {
-        fn lex_keyword(source: &[char]) -> Option<FoundToken> {
+        fn lex_punctuation(source: &[char]) -> Option<FoundToken> {
             $(
                 if let Some(found) = lex_characters(source, $text, TokenKind::$res){
                     return Some(found);
@@ -198,9 +371,20 @@ macro_rules! lex_chars_to {
 
             None
         }
+
+        /// How a punctuation token is written in source code.
+        pub fn punctuation_text(kind: ShallowTokenKind) -> Option<&'static str> {
+            match kind {
+                $(
+                    ShallowTokenKind::$res => Some($text),
+                )*
+                _ => None,
+            }
+        }
     };
 }
 
+// Longer operators have to come before any operator that is a prefix of them.
 lex_chars_to! {
     "(" => LeftParen,
     ")" => RightParen,
@@ -210,13 +394,30 @@ lex_chars_to! {
     "]" => RightBracket,
     "," => Comma,
     "==" => DoubleEquals,
+    "=>" => FatArrow,
     "=" => Equals,
     "+=" => AddEquals,
     "-=" => SubtractEquals,
+    "**=" => PowEquals,
     "*=" => MultiplyEquals,
     "/=" => DivideEquals,
+    "~/=" => IntegerDivideEquals,
+    "%=" => RemainderEquals,
+    "&=" => BitAndEquals,
+    "|=" => BitOrEquals,
+    "^=" => BitXorEquals,
+    "<<=" => ShiftLeftEquals,
+    ">>=" => ShiftRightEquals,
+    "!=" => BangEquals,
+    ">>" => DoubleGreaterThan,
+    ">=" => GreaterThanEquals,
     ">" => GreaterThan,
+    "<<" => DoubleLessThan,
+    "<=" => LessThanEquals,
     "<" => LessThan,
+    "..." => TripleDot,
+    ".." => DoubleDot,
+    "." => Dot,
     ":" => Colon,
     ";" => Semicolon,
     "+" => Plus,
@@ -224,14 +425,287 @@ lex_chars_to! {
     "**" => DoubleAsterisk,
     "*" => Asterisk,
     "/" => ForwardSlash,
+    "~/" => TildeSlash,
+    "%" => Percent,
+    "!" => Bang,
+    "&&" => DoubleAmpersand,
+    "&" => Ampersand,
+    "||" => DoublePipe,
+    "|" => Pipe,
+    "^" => Caret
+}
+
+macro_rules! keywords {
+    ($($text:literal => $res:ident),*) => {
+        /// The token a word lexes to if it is reserved by the language.
+        fn keyword_from_word(word: &str) -> Option<TokenKind> {
+            match word {
+                $(
+                    $text => Some(TokenKind::$res),
+                )*
+                _ => None,
+            }
+        }
+
+        /// How a keyword is written in source code.
+        pub fn keyword_text(kind: ShallowTokenKind) -> Option<&'static str> {
+            match kind {
+                $(
+                    ShallowTokenKind::$res => Some($text),
+                )*
+                _ => None,
+            }
+        }
+
+        #[cfg(test)]
+        const KEYWORDS: &[&str] = &[$($text),*];
+    };
+}
+
+keywords! {
     "true" => True,
     "false" => False,
+    "null" => Null,
     "let" => Let,
     "fn" => Fn,
     "while" => While,
     "return" => Return,
+    "yield" => Yield,
     "break" => Break,
     "continue" => Continue,
     "if" => If,
+    "for" => For,
+    "in" => In,
     "else" => Else
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{lex_number, lex_to_end, TokenIter, KEYWORDS, MIN_READ};
+    use crate::lex::token::Span;
+    use crate::lex::TokenKind;
+
+    fn lex_kinds(source: &str) -> Vec<TokenKind> {
+        let chars: Vec<char> = source.chars().collect();
+
+        lex_to_end(&chars)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    fn lex_number_str(source: &str) -> Option<(f64, usize)> {
+        let chars: Vec<char> = source.chars().collect();
+
+        lex_number(&chars).map(|found| (found.token.number().unwrap(), found.next_index))
+    }
+
+    #[test]
+    fn lexes_numbers() {
+        assert_eq!(lex_number_str("23"), Some((23., 2)));
+        assert_eq!(lex_number_str("4.5"), Some((4.5, 3)));
+        assert_eq!(lex_number_str("1.5e3;"), Some((1500., 5)));
+        assert_eq!(lex_number_str("2E-2"), Some((0.02, 4)));
+    }
+
+    #[test]
+    fn leaves_trailing_dot_and_exponent() {
+        assert_eq!(lex_number_str("1."), Some((1., 1)));
+        assert_eq!(lex_number_str("3e"), Some((3., 1)));
+        assert_eq!(lex_number_str("4e+"), Some((4., 1)));
+    }
+
+    #[test]
+    fn does_not_lex_words_as_numbers() {
+        assert_eq!(lex_number_str("inf"), None);
+        assert_eq!(lex_number_str("NaN"), None);
+        assert_eq!(lex_number_str("-4"), None);
+    }
+
+    #[test]
+    fn lexes_minus_separately() {
+        assert_eq!(
+            lex_kinds("1 -4"),
+            vec![
+                TokenKind::Number(1.),
+                TokenKind::Minus,
+                TokenKind::Number(4.)
+            ]
+        );
+    }
+
+    #[test]
+    fn lexes_longest_operator() {
+        assert_eq!(
+            lex_kinds("a **= b >>= 1 >> 2 >= 3 ~/ 4 != !c && d & e"),
+            vec![
+                TokenKind::Ident("a".to_string()),
+                TokenKind::PowEquals,
+                TokenKind::Ident("b".to_string()),
+                TokenKind::ShiftRightEquals,
+                TokenKind::Number(1.),
+                TokenKind::DoubleGreaterThan,
+                TokenKind::Number(2.),
+                TokenKind::GreaterThanEquals,
+                TokenKind::Number(3.),
+                TokenKind::TildeSlash,
+                TokenKind::Number(4.),
+                TokenKind::BangEquals,
+                TokenKind::Bang,
+                TokenKind::Ident("c".to_string()),
+                TokenKind::DoubleAmpersand,
+                TokenKind::Ident("d".to_string()),
+                TokenKind::Ampersand,
+                TokenKind::Ident("e".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lexes_long_source() {
+        let source: Vec<char> = "1 + 2;\n".repeat(20_000).chars().collect();
+
+        let tokens = lex_to_end(&source).unwrap();
+
+        assert_eq!(tokens.len(), 80_000);
+    }
+
+    #[test]
+    fn iterator_stops_at_first_error() {
+        let mut iter = TokenIter::new("1 2 \"unclosed");
+
+        assert_eq!(iter.next().unwrap().unwrap().kind, TokenKind::Number(1.));
+        assert_eq!(iter.next().unwrap().unwrap().kind, TokenKind::Number(2.));
+
+        let err = iter.next().unwrap().unwrap_err();
+        assert_eq!(err.index, 4);
+        assert!(err.kind.is_unterminated_string());
+
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn iterator_matches_lexing_everything() {
+        let snippet = "let café = 1.5e3 + 0..10; // comment\n/* block\n comment */ s = \"${a ** 2} \u{1F600}\" ...x;\n";
+        let source = snippet.repeat(50) + &format!("\"{}\" <<= 3", "long ".repeat(100));
+
+        let chars: Vec<char> = source.chars().collect();
+        let lazy: Vec<_> = TokenIter::new(&source).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(lazy, lex_to_end(&chars).unwrap());
+    }
+
+    #[test]
+    fn iterator_reads_source_as_needed() {
+        let source = "let a = 1; ".repeat(1000);
+        let mut iter = TokenIter::new(&source);
+
+        assert_eq!(iter.next().unwrap().unwrap().kind, TokenKind::Let);
+        assert!(iter.chars.as_str().len() > source.len() - 100);
+    }
+
+    #[test]
+    fn iterator_returns_errors_without_reading_the_rest() {
+        for error in ["@", "\"\\q\"", "\"\\u{zz}\""] {
+            let source = format!("let a = {error}; ") + &"let b = 2; ".repeat(10_000);
+            let mut iter = TokenIter::new(&source);
+
+            assert!(iter.by_ref().any(|res| res.is_err()));
+            assert!(iter.chars.as_str().len() > source.len() - 100, "{error}");
+        }
+    }
+
+    #[test]
+    fn iterator_reads_on_for_tokens_cut_off() {
+        // Pads the sources so the first read ends partway through
+        let source = format!("{}\"\\u{{1F600}}\"", " ".repeat(MIN_READ - 5));
+        let tokens: Vec<_> = TokenIter::new(&source).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::String("\u{1F600}".to_string()));
+
+        let source = format!("{}~/ 2", " ".repeat(MIN_READ - 1));
+        let tokens: Vec<_> = TokenIter::new(&source).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::TildeSlash);
+    }
+
+    #[test]
+    fn lexes_whole_keywords() {
+        for keyword in KEYWORDS {
+            let kinds = lex_kinds(keyword);
+
+            assert_eq!(kinds.len(), 1);
+            assert!(!kinds[0].is_ident(), "`{keyword}` lexed as an identifier");
+        }
+    }
+
+    #[test]
+    fn keyword_prefixes_lex_as_identifiers() {
+        for keyword in KEYWORDS {
+            for suffix in ["ter", "fy", "_", "_name", "2", "é"] {
+                let word = format!("{keyword}{suffix}");
+
+                assert_eq!(lex_kinds(&word), vec![TokenKind::Ident(word)]);
+            }
+        }
+    }
+
+    #[test]
+    fn keyword_suffixes_lex_as_identifiers() {
+        for keyword in KEYWORDS {
+            let word = format!("is_{keyword}");
+
+            assert_eq!(lex_kinds(&word), vec![TokenKind::Ident(word)]);
+        }
+    }
+
+    #[test]
+    fn lexes_unicode_identifiers() {
+        for ident in ["café", "变量", "_private", "ñandú2", "Δx"] {
+            assert_eq!(lex_kinds(ident), vec![TokenKind::Ident(ident.to_string())]);
+        }
+    }
+
+    #[test]
+    fn identifiers_end_at_operators() {
+        assert_eq!(
+            lex_kinds("letter+iffy"),
+            vec![
+                TokenKind::Ident("letter".to_string()),
+                TokenKind::Plus,
+                TokenKind::Ident("iffy".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn spans_are_in_bytes() {
+        let chars: Vec<char> = "\"é\" + \"${ñ}\"".chars().collect();
+
+        let tokens = lex_to_end(&chars).unwrap();
+        assert_eq!(tokens[0].span, Span::new(0, 4));
+        assert_eq!(tokens[1].span, Span::new(5, 6));
+        assert_eq!(tokens[2].span, Span::new(7, 14));
+
+        let TokenKind::Template(template) = &tokens[2].kind else {
+            panic!("Expected a template.");
+        };
+        assert_eq!(
+            template.parts[0].as_tokens().unwrap()[0].span,
+            Span::new(10, 12)
+        );
+
+        let err = lex_to_end(&"é \"".chars().collect::<Vec<_>>()).unwrap_err();
+        assert_eq!(err.index, 3);
+    }
+
+    #[test]
+    fn rejects_non_xid_identifiers() {
+        let chars: Vec<char> = "let a = 2 € 3;".chars().collect();
+
+        let err = lex_to_end(&chars).unwrap_err();
+        assert_eq!(err.index, 10);
+        assert!(err.kind.is_unexpected_character());
+    }
+}
@@ -1,7 +1,8 @@
 mod error;
 mod lexers;
+mod string_lexers;
 mod token;
 
-pub use error::Error;
-pub use lexers::lex_to_end;
-pub use token::{ShallowTokenKind, Token, TokenKind};
+pub use error::{Error, ErrorKind};
+pub use lexers::{lex_to_end, TokenIter};
+pub use token::{ShallowTokenKind, Template, TemplatePart, Token, TokenKind};
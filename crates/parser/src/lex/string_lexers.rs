@@ -0,0 +1,294 @@
+use super::lexers::{lex_next, FoundToken};
+use super::token::{Template, TemplatePart, Token, TokenKind};
+use super::{Error, ErrorKind};
+
+/// Lexes any of the string literals:
+///
+/// - `"text"`, where escape sequences like `\n` are processed and `${...}` embeds an expression.
+/// - `"""text"""`, which is processed the same way, but can contain unescaped quotes.
+/// - `r"text"` or `r#"text"#`, where nothing is escaped.
+///
+/// Returns `Ok(None)` if the source doesn't start with a string.
+pub fn lex_string(source: &[char]) -> Result<Option<FoundToken>, Error> {
+    match source {
+        ['r', ..] => Ok(lex_raw_string(source).transpose()?),
+        ['"', '"', '"', ..] => lex_escaped_string(source, 3).map(Some),
+        ['"', ..] => lex_escaped_string(source, 1).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Lexes a string delimited by `quote_count` quotes on either side, processing escape sequences.
+///
+/// If the string contains any `${...}`, it is lexed as a [`Template`] instead.
+fn lex_escaped_string(source: &[char], quote_count: usize) -> Result<FoundToken, Error> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut index = quote_count;
+
+    loop {
+        match source.get(index..) {
+            // A trailing backslash escapes nothing, so the string can't have been closed either
+            None | Some([] | ['\\']) => return Err(Error::new(0, ErrorKind::UnterminatedString)),
+            Some(rest) if is_closing_quotes(rest, quote_count) => {
+                let token = if parts.is_empty() {
+                    TokenKind::String(text)
+                } else {
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Literal(text));
+                    }
+
+                    TokenKind::Template(Template { parts })
+                };
+
+                return Ok(FoundToken {
+                    next_index: index + quote_count,
+                    token,
+                });
+            }
+            Some(['$', '{', ..]) => {
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Literal(std::mem::take(&mut text)));
+                }
+
+                let (tokens, next_index) = lex_interpolation(source, index)?;
+                parts.push(TemplatePart::Tokens(tokens));
+                index = next_index;
+            }
+            Some(['\\', ..]) => {
+                let (c, len) = lex_escape(&source[index..]).map_err(|err| err.offset(index))?;
+                text.push(c);
+                index += len;
+            }
+            Some([c, ..]) => {
+                text.push(*c);
+                index += 1;
+            }
+        }
+    }
+}
+
+/// Lexes the tokens of the `${...}` starting at `start`, up to the matching `}`.
+///
+/// Returns the tokens, along with the index after the `}`.
+fn lex_interpolation(source: &[char], start: usize) -> Result<(Vec<Token>, usize), Error> {
+    let mut cursor = start + 2;
+    let mut depth = 0;
+    let mut tokens = Vec::new();
+
+    loop {
+        let Some(token) = lex_next(source, &mut cursor) else {
+            return Err(Error::new(start, ErrorKind::UnterminatedInterpolation));
+        };
+        let token = token?;
+
+        match token.kind {
+            TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightBrace if depth == 0 => return Ok((tokens, cursor)),
+            TokenKind::RightBrace => depth -= 1,
+            _ => (),
+        }
+
+        tokens.push(token);
+    }
+}
+
+fn is_closing_quotes(source: &[char], quote_count: usize) -> bool {
+    source.len() >= quote_count && source[..quote_count].iter().all(|c| *c == '"')
+}
+
+/// Lexes the escape sequence at the start of the source,
+/// which must be a backslash followed by at least one character.
+///
+/// Returns the escaped character and the length of the sequence.
+fn lex_escape(source: &[char]) -> Result<(char, usize), Error> {
+    let c = match source[1] {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' => '\\',
+        '"' => '"',
+        '\'' => '\'',
+        '$' => '$',
+        'u' => return lex_unicode_escape(source),
+        c => return Err(Error::new(0, ErrorKind::InvalidEscape(c))),
+    };
+
+    Ok((c, 2))
+}
+
+/// Lexes an escape of the form `\u{1F600}`, with between one and six hexadecimal digits.
+fn lex_unicode_escape(source: &[char]) -> Result<(char, usize), Error> {
+    let invalid = || Error::new(0, ErrorKind::InvalidUnicodeEscape);
+
+    if source.get(2) != Some(&'{') {
+        return Err(invalid());
+    }
+
+    let digits: String = source[3..]
+        .iter()
+        .take_while(|c| c.is_ascii_hexdigit())
+        .collect();
+
+    if digits.is_empty() || digits.len() > 6 || source.get(3 + digits.len()) != Some(&'}') {
+        return Err(invalid());
+    }
+
+    let c = u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(invalid)?;
+
+    Ok((c, 4 + digits.len()))
+}
+
+/// Lexes a string of the form `r"text"` or `r#"text"#`, with any number of `#`.
+///
+/// Returns `None` if the source is just an identifier that starts with `r`.
+fn lex_raw_string(source: &[char]) -> Option<Result<FoundToken, Error>> {
+    let hash_count = source[1..].iter().take_while(|c| **c == '#').count();
+
+    if source.get(1 + hash_count) != Some(&'"') {
+        return None;
+    }
+
+    let start = hash_count + 2;
+
+    for index in start..source.len() {
+        let closes = source[index] == '"'
+            && source.len() > index + hash_count
+            && source[index + 1..=index + hash_count]
+                .iter()
+                .all(|c| *c == '#');
+
+        if closes {
+            return Some(Ok(FoundToken {
+                next_index: index + 1 + hash_count,
+                token: TokenKind::String(source[start..index].iter().collect()),
+            }));
+        }
+    }
+
+    Some(Err(Error::new(0, ErrorKind::UnterminatedString)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lex_string;
+    use crate::lex::token::Span;
+    use crate::lex::{Error, ErrorKind, TemplatePart, TokenKind};
+
+    fn lex(source: &str) -> Result<Option<(String, usize)>, Error> {
+        let chars: Vec<char> = source.chars().collect();
+
+        lex_string(&chars)
+            .map(|found| found.map(|found| (found.token.string().unwrap(), found.next_index)))
+    }
+
+    fn lex_text(source: &str) -> String {
+        lex(source).unwrap().unwrap().0
+    }
+
+    fn lex_err(source: &str) -> (usize, ErrorKind) {
+        let err = lex(source).unwrap_err();
+        (err.index, err.kind)
+    }
+
+    #[test]
+    fn lexes_plain_string() {
+        assert_eq!(lex(r#""test" + 1"#).unwrap(), Some(("test".to_string(), 6)));
+    }
+
+    #[test]
+    fn processes_escapes() {
+        assert_eq!(lex_text(r#""a\"b\\c\nd\te\0""#), "a\"b\\c\nd\te\0");
+        assert_eq!(lex_text(r#""\u{1F600} \u{41}""#), "\u{1F600} A");
+    }
+
+    #[test]
+    fn lexes_triple_quoted_string() {
+        let source = "\"\"\"first \"line\"\n\\tsecond\"\"\" + 1";
+
+        assert_eq!(
+            lex(source).unwrap(),
+            Some(("first \"line\"\n\tsecond".to_string(), 27))
+        );
+    }
+
+    #[test]
+    fn lexes_raw_strings() {
+        assert_eq!(lex_text(r#"r"C:\path\n""#), r"C:\path\n");
+        assert_eq!(lex_text(r##"r#"say "hi""#"##), r#"say "hi""#);
+    }
+
+    #[test]
+    fn ignores_identifiers_starting_with_r() {
+        assert_eq!(lex("result").unwrap(), None);
+        assert_eq!(lex("r#").unwrap(), None);
+    }
+
+    #[test]
+    fn reports_bad_escapes() {
+        assert_eq!(lex_err(r#""ab\q""#), (3, ErrorKind::InvalidEscape('q')));
+        assert_eq!(
+            lex_err(r#""\u{110000}""#),
+            (1, ErrorKind::InvalidUnicodeEscape)
+        );
+        assert_eq!(lex_err(r#""\u{}""#), (1, ErrorKind::InvalidUnicodeEscape));
+        assert_eq!(lex_err(r#""\u41""#), (1, ErrorKind::InvalidUnicodeEscape));
+    }
+
+    #[test]
+    fn reports_unterminated_strings() {
+        assert_eq!(lex_err(r#""abc"#), (0, ErrorKind::UnterminatedString));
+        assert_eq!(lex_err(r#""""abc""#), (0, ErrorKind::UnterminatedString));
+        assert_eq!(lex_err(r##"r#"abc""##), (0, ErrorKind::UnterminatedString));
+        assert_eq!(lex_err(r#""abc\"#), (0, ErrorKind::UnterminatedString));
+    }
+
+    #[test]
+    fn lexes_template() {
+        let chars: Vec<char> = r#""Hi ${user["name"]}, ${ {a: 1} }!\${x}""#.chars().collect();
+
+        let found = lex_string(&chars).unwrap().unwrap();
+        let TokenKind::Template(template) = found.token else {
+            panic!("Expected a template.");
+        };
+
+        assert_eq!(found.next_index, chars.len());
+        assert_eq!(template.parts.len(), 5);
+        assert_eq!(template.parts[0], TemplatePart::Literal("Hi ".to_string()));
+        assert_eq!(
+            template.parts[4],
+            TemplatePart::Literal("!${x}".to_string())
+        );
+
+        let TemplatePart::Tokens(tokens) = &template.parts[1] else {
+            panic!("Expected an embedded expression.");
+        };
+
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0].span, Span::new(6, 10));
+        assert_eq!(template.parts[3].as_tokens().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn lexes_nested_template() {
+        let chars: Vec<char> = r#""a ${"b ${c}"} d""#.chars().collect();
+
+        let found = lex_string(&chars).unwrap().unwrap();
+        let template = found.token.template().unwrap();
+        let tokens = template.parts[1].as_tokens().unwrap();
+
+        assert!(tokens[0].kind.is_template());
+    }
+
+    #[test]
+    fn reports_unterminated_interpolation() {
+        assert_eq!(
+            lex_err(r#""a ${b"#),
+            (3, ErrorKind::UnterminatedInterpolation)
+        );
+    }
+}
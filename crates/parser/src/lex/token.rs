@@ -1,24 +1,61 @@
 use std::fmt::{Display, Formatter};
 
-use ast::{AssignOpKind, BinaryOpKind};
+pub use ast::Span;
+use ast::{AssignOpKind, BinaryOpKind, UnaryOpKind};
 use is_macro::Is;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Span {
-    pub start: usize,
-    pub end: usize,
+use super::lexers::{keyword_text, punctuation_text};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub span: Span,
+    pub kind: TokenKind,
 }
 
-impl Span {
-    pub fn new(start: usize, end: usize) -> Self {
-        Self { start, end }
+impl Token {
+    /// Moves the token, along with any tokens nested inside of it, by an index.
+    pub fn offset(&mut self, by: usize) {
+        self.map_spans(&|span| span.offset(by));
+    }
+
+    /// Changes the span of the token, along with the spans of any tokens nested inside of it.
+    pub fn map_spans(&mut self, f: &impl Fn(Span) -> Span) {
+        self.span = f(self.span);
+
+        if let TokenKind::Template(template) = &mut self.kind {
+            for part in &mut template.parts {
+                if let TemplatePart::Tokens(tokens) = part {
+                    tokens.iter_mut().for_each(|token| token.map_spans(f));
+                }
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Token {
-    pub span: Span,
-    pub kind: TokenKind,
+/// The contents of a string literal that contains at least one `${...}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Is)]
+pub enum TemplatePart {
+    Literal(String),
+    /// The tokens between the `${` and `}` of an embedded expression.
+    Tokens(Vec<Token>),
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => write!(f, "{text}")?,
+                TemplatePart::Tokens(_) => write!(f, "${{...}}")?,
+            }
+        }
+
+        Ok(())
+    }
 }
 
 macro_rules! define_token_types {
@@ -73,36 +110,98 @@ macro_rules! define_token_types {
     };
 }
 
+impl ShallowTokenKind {
+    /// How the token is written in source code, if every token of this kind is written the same way.
+    pub fn text(self) -> Option<&'static str> {
+        punctuation_text(self).or_else(|| keyword_text(self))
+    }
+
+    /// Describes the kind of token for error messages, like `` `;` `` or `an identifier`.
+    pub fn describe(self) -> String {
+        if let Some(text) = self.text() {
+            return format!("`{text}`");
+        }
+
+        match self {
+            ShallowTokenKind::Ident => "an identifier",
+            ShallowTokenKind::Number => "a number",
+            ShallowTokenKind::String | ShallowTokenKind::Template => "a string",
+            _ => return self.to_string(),
+        }
+        .to_string()
+    }
+}
+
 impl TokenKind {
+    /// Describes the token for error messages, including its name or value if it has one.
+    pub fn describe(&self) -> String {
+        match self {
+            TokenKind::Ident(ident) => format!("`{ident}`"),
+            TokenKind::Number(n) => format!("`{n}`"),
+            other => other.as_shallow().describe(),
+        }
+    }
+
     pub fn as_binary_op(&self) -> Option<BinaryOpKind> {
         match self {
             TokenKind::Plus => Some(BinaryOpKind::Add),
             TokenKind::Minus => Some(BinaryOpKind::Subtract),
             TokenKind::Asterisk => Some(BinaryOpKind::Multiply),
             TokenKind::ForwardSlash => Some(BinaryOpKind::Divide),
+            TokenKind::TildeSlash => Some(BinaryOpKind::IntegerDivide),
+            TokenKind::Percent => Some(BinaryOpKind::Remainder),
             TokenKind::DoubleEquals => Some(BinaryOpKind::Equals),
+            TokenKind::BangEquals => Some(BinaryOpKind::NotEquals),
             TokenKind::GreaterThan => Some(BinaryOpKind::GreaterThan),
+            TokenKind::GreaterThanEquals => Some(BinaryOpKind::GreaterThanOrEquals),
             TokenKind::DoubleAsterisk => Some(BinaryOpKind::Pow),
             TokenKind::LessThan => Some(BinaryOpKind::LessThan),
+            TokenKind::LessThanEquals => Some(BinaryOpKind::LessThanOrEquals),
+            TokenKind::Ampersand => Some(BinaryOpKind::BitAnd),
+            TokenKind::Pipe => Some(BinaryOpKind::BitOr),
+            TokenKind::Caret => Some(BinaryOpKind::BitXor),
+            TokenKind::DoubleLessThan => Some(BinaryOpKind::ShiftLeft),
+            TokenKind::DoubleGreaterThan => Some(BinaryOpKind::ShiftRight),
+            TokenKind::DoubleAmpersand => Some(BinaryOpKind::And),
+            TokenKind::DoublePipe => Some(BinaryOpKind::Or),
             _ => None,
         }
     }
 
-    pub fn as_assign_op(&self) -> Option<AssignOpKind> {
+    pub fn as_unary_op(&self) -> Option<UnaryOpKind> {
         match self {
-            TokenKind::Equals => Some(AssignOpKind::NoOp),
-            TokenKind::AddEquals => Some(AssignOpKind::Op(BinaryOpKind::Add)),
-            TokenKind::SubtractEquals => Some(AssignOpKind::Op(BinaryOpKind::Subtract)),
-            TokenKind::MultiplyEquals => Some(AssignOpKind::Op(BinaryOpKind::Multiply)),
-            TokenKind::DivideEquals => Some(AssignOpKind::Op(BinaryOpKind::Divide)),
+            TokenKind::Minus => Some(UnaryOpKind::Negate),
+            TokenKind::Bang => Some(UnaryOpKind::Not),
             _ => None,
         }
     }
+
+    pub fn as_assign_op(&self) -> Option<AssignOpKind> {
+        let op = match self {
+            TokenKind::Equals => return Some(AssignOpKind::NoOp),
+            TokenKind::AddEquals => BinaryOpKind::Add,
+            TokenKind::SubtractEquals => BinaryOpKind::Subtract,
+            TokenKind::MultiplyEquals => BinaryOpKind::Multiply,
+            TokenKind::DivideEquals => BinaryOpKind::Divide,
+            TokenKind::IntegerDivideEquals => BinaryOpKind::IntegerDivide,
+            TokenKind::RemainderEquals => BinaryOpKind::Remainder,
+            TokenKind::PowEquals => BinaryOpKind::Pow,
+            TokenKind::BitAndEquals => BinaryOpKind::BitAnd,
+            TokenKind::BitOrEquals => BinaryOpKind::BitOr,
+            TokenKind::BitXorEquals => BinaryOpKind::BitXor,
+            TokenKind::ShiftLeftEquals => BinaryOpKind::ShiftLeft,
+            TokenKind::ShiftRightEquals => BinaryOpKind::ShiftRight,
+            _ => return None,
+        };
+
+        Some(AssignOpKind::Op(op))
+    }
 }
 
 define_token_types! {
     Number(f64),
     String(String),
+    Template(Template),
     Ident(String),
     LeftParen,
     RightParen,
@@ -117,23 +216,52 @@ define_token_types! {
     SubtractEquals,
     MultiplyEquals,
     DivideEquals,
+    IntegerDivideEquals,
+    RemainderEquals,
+    PowEquals,
+    BitAndEquals,
+    BitOrEquals,
+    BitXorEquals,
+    ShiftLeftEquals,
+    ShiftRightEquals,
+    BangEquals,
     GreaterThan,
+    GreaterThanEquals,
     LessThan,
+    LessThanEquals,
     Let,
     Fn,
     Return,
+    Yield,
     Break,
     Continue,
     While,
     If,
+    For,
+    In,
     Else,
     Plus,
     Minus,
     Asterisk,
     DoubleAsterisk,
     ForwardSlash,
+    TildeSlash,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    DoubleLessThan,
+    DoubleGreaterThan,
+    Bang,
+    DoubleAmpersand,
+    DoublePipe,
     True,
     False,
+    Null,
+    Dot,
+    DoubleDot,
+    TripleDot,
     Colon,
-    Semicolon
+    Semicolon,
+    FatArrow
 }
@@ -4,7 +4,10 @@ mod lex;
 mod parse;
 
 use ast::Program;
-pub use lex::{Error as LexError, Token, TokenKind};
+pub use lex::{
+    Error as LexError, ErrorKind as LexErrorKind, Template, TemplatePart, Token, TokenIter,
+    TokenKind,
+};
 pub use parse::{Error as ParseError, ErrorKind as ParseErrorKind};
 
 #[derive(Debug, thiserror::Error)]
@@ -15,6 +18,15 @@ pub enum Error {
     Parse(#[from] ParseError),
 }
 
+impl Error {
+    pub fn to_diagnostic(&self) -> diagnostics::Diagnostic {
+        match self {
+            Error::Lex(err) => err.to_diagnostic(),
+            Error::Parse(err) => err.to_diagnostic(),
+        }
+    }
+}
+
 /// Completely lex a string into a series of tokens.
 pub fn lex_string(source: &str) -> Result<Vec<Token>, LexError> {
     let seperated: Vec<_> = source.chars().collect();
@@ -22,22 +34,52 @@ pub fn lex_string(source: &str) -> Result<Vec<Token>, LexError> {
     lex::lex_to_end(&seperated)
 }
 
+/// Lazily lex a string, one token at a time.
+///
+/// The iterator ends after the first error, so callers can stop without lexing the rest of the source.
+pub fn lex_iter(source: &str) -> TokenIter<'_> {
+    TokenIter::new(source)
+}
+
 /// Completely parse tokens into an AST.
 pub fn parse_tokens(tokens: &[Token]) -> Result<Program, ParseError> {
     parse::parse_stmt_list(tokens)
 }
 
+/// Parse tokens into an AST, recovering from errors instead of stopping at the first one.
+///
+/// Parsing picks up again after the `;` or `}` that ends a statement which failed to parse, and the statement is
+/// left in the program as an [`ast::StmtKind::Error`]. Every error is returned, in the order they appear.
+pub fn parse_tokens_recovering(tokens: &[Token]) -> (Program, Vec<ParseError>) {
+    parse::parse_stmt_list_recovering(tokens)
+}
+
 /// Function that does both [`lex_string`] and [`parse_tokens`].
 ///
 /// This is mosty likely what you want to use, unless there is some special circumstance that involves
 /// caching tokens.
 pub fn parse_string(source: &str) -> Result<Program, Error> {
     let tokens = lex_string(source)?;
-    let program = parse::parse_stmt_list(&tokens)?;
+    let program = parse::parse_stmt_list(&tokens).map_err(|err| err.with_source(source))?;
 
     Ok(program)
 }
 
+/// Function that does both [`lex_string`] and [`parse_tokens_recovering`].
+///
+/// Lexing still stops at the first error, since there is no reliable way to tell where the next token starts.
+pub fn parse_string_recovering(source: &str) -> Result<(Program, Vec<ParseError>), LexError> {
+    let tokens = lex_string(source)?;
+    let (program, errors) = parse::parse_stmt_list_recovering(&tokens);
+
+    let errors = errors
+        .into_iter()
+        .map(|err| err.with_source(source))
+        .collect();
+
+    Ok((program, errors))
+}
+
 #[cfg(test)]
 mod test_utils {
     use crate::lex::{lex_to_end, Token};
@@ -1,129 +1,74 @@
-use ast::Expr;
+use ast::{Expr, ExprKind};
 
+use super::cursor::Cursor;
 use super::expr_parsers::parse_expr;
-use super::tokens_ext::TokensExt;
 use super::Error;
-use crate::lex::{ShallowTokenKind, Token};
-
-#[derive(Debug, Clone)]
-pub struct FoundExprList {
-    pub items: Vec<FoundExprListItem>,
-    pub next_index: usize,
-}
-
-impl FoundExprList {
-    /// Consuming iterator over constituent `Expr`s
-    pub fn iter_exprs(self) -> impl Iterator<Item = Expr> {
-        self.items.into_iter().map(|t| t.expr)
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct FoundExprListItem {
-    pub expr: Expr,
-    pub found_at: usize,
-}
-
-#[derive(Debug, Clone)]
-pub struct FoundPropIdentList {
-    pub prop_idents: Vec<String>,
-    pub next_index: usize,
-}
+use crate::lex::ShallowTokenKind;
 
+/// Parses a list of expressions, like the arguments of a function call or the items of an array.
+/// Each item may be spread with `...`.
+///
+/// Expects the cursor to be on the `open` token and leaves it after the `close` token.
 pub fn parse_expr_list(
-    tokens: &[Token],
+    cursor: &mut Cursor,
     separator: ShallowTokenKind,
     open: ShallowTokenKind,
     close: ShallowTokenKind,
-) -> Result<FoundExprList, Error> {
-    let closing_index = tokens.locate_last_matched_right(open, close)?;
-
-    if closing_index == 1 {
-        return Ok(FoundExprList {
-            items: Vec::new(),
-            next_index: 2,
-        });
-    }
+) -> Result<Vec<Expr>, Error> {
+    cursor.expect(open)?;
 
-    let mut current_start = 1;
     let mut items = Vec::new();
-    let mut d = 0;
 
-    while current_start < closing_index {
-        let current = &tokens[current_start..closing_index - d];
+    if cursor.eat(close) {
+        return Ok(items);
+    }
 
-        if current.is_empty() {
-            return Err(Error::no_valid_expr(current_start)).map_err(Error::unrecoverable);
-        }
+    loop {
+        let start = cursor.index();
 
-        if d != 0 && tokens.get_token_kind(closing_index - d, separator).is_err() {
-            d += 1;
-            continue;
-        }
+        let item = if cursor.eat(ShallowTokenKind::TripleDot) {
+            let spread = parse_expr(cursor)?;
+            Expr::new(ExprKind::Spread(Box::new(spread)), cursor.span_from(start))
+        } else {
+            parse_expr(cursor)?
+        };
+
+        items.push(item);
 
-        match parse_expr(current) {
-            Ok(expr) => {
-                items.push(FoundExprListItem {
-                    expr,
-                    found_at: current_start,
-                });
-                current_start += current.len() + 1;
-                d = 0;
-            }
-            Err(err) => {
-                if let Error {
-                    is_recoverable: true,
-                    ..
-                } = err
-                {
-                    d += 1
-                } else {
-                    return Err(err);
-                }
-            }
+        if cursor.eat(close) {
+            return Ok(items);
         }
-    }
 
-    Ok(FoundExprList {
-        items,
-        next_index: closing_index + 1,
-    })
+        cursor.expect(separator)?;
+    }
 }
 
-/// Parses the arguments for a function call
-pub fn parse_prop_ident_list(tokens: &[Token]) -> Result<FoundPropIdentList, Error> {
-    let FoundExprList { items, next_index } = parse_expr_list(
-        tokens,
-        ShallowTokenKind::Comma,
-        ShallowTokenKind::LeftParen,
-        ShallowTokenKind::RightParen,
-    )?;
+/// Parses the parameter identifiers of a function declaration, like `(a, b, c)`.
+pub fn parse_prop_ident_list(cursor: &mut Cursor) -> Result<Vec<String>, Error> {
+    cursor.expect(ShallowTokenKind::LeftParen)?;
 
     let mut prop_idents = Vec::new();
 
-    for item in items {
-        if let Some(ident) = item.expr.ident() {
-            prop_idents.push(ident);
-        } else {
-            return Err(Error::expected_token(
-                item.found_at,
-                ShallowTokenKind::Ident,
-                Some(tokens[item.found_at].clone()),
-            )
-            .unrecoverable());
-        }
+    if cursor.eat(ShallowTokenKind::RightParen) {
+        return Ok(prop_idents);
     }
 
-    Ok(FoundPropIdentList {
-        prop_idents,
-        next_index,
-    })
+    loop {
+        prop_idents.push(cursor.expect_ident()?);
+
+        if cursor.eat(ShallowTokenKind::RightParen) {
+            return Ok(prop_idents);
+        }
+
+        cursor.expect(ShallowTokenKind::Comma)?;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{parse_expr_list, parse_prop_ident_list};
     use crate::lex::ShallowTokenKind;
+    use crate::parse::cursor::Cursor;
     use crate::test_utils::tokenize;
 
     #[test]
@@ -131,21 +76,37 @@ mod tests {
         let tokens = tokenize("()");
 
         let res = parse_expr_list(
-            &tokens,
+            &mut Cursor::new(&tokens),
             ShallowTokenKind::Comma,
             ShallowTokenKind::LeftParen,
             ShallowTokenKind::RightParen,
         );
 
-        res.unwrap();
+        assert!(res.unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_spread_items() {
+        let tokens = tokenize("[a, ...b]");
+
+        let res = parse_expr_list(
+            &mut Cursor::new(&tokens),
+            ShallowTokenKind::Comma,
+            ShallowTokenKind::LeftBracket,
+            ShallowTokenKind::RightBracket,
+        )
+        .unwrap();
+
+        assert!(res[0].kind.is_ident());
+        assert!(res[1].kind.is_spread());
     }
 
     #[test]
     fn parses_prop_list() {
         let tokens = tokenize("(a, b, c)");
 
-        let props = parse_prop_ident_list(&tokens).unwrap();
+        let props = parse_prop_ident_list(&mut Cursor::new(&tokens)).unwrap();
 
-        assert_eq!(props.prop_idents, vec!["a", "b", "c"])
+        assert_eq!(props, vec!["a", "b", "c"])
     }
 }
@@ -0,0 +1,138 @@
+use ast::Span;
+
+use super::Error;
+use crate::lex::{ShallowTokenKind, Token, TokenKind};
+
+/// A position inside of a list of tokens.
+///
+/// Parsers move the cursor forward as they consume tokens, so each token is only visited once.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    tokens: &'a [Token],
+    index: usize,
+    /// Whether statements that fail to parse should be skipped, rather than stopping the whole parse.
+    recovering: bool,
+    /// The errors that were recovered from, in the order they were found.
+    errors: Vec<Error>,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        Self {
+            tokens,
+            index: 0,
+            recovering: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// A cursor for parsing in recovery mode, which collects errors instead of stopping at the first one.
+    pub fn recovering(tokens: &'a [Token]) -> Self {
+        Self {
+            recovering: true,
+            ..Self::new(tokens)
+        }
+    }
+
+    pub fn is_recovering(&self) -> bool {
+        self.recovering
+    }
+
+    /// Keeps an error that was recovered from.
+    pub fn report(&mut self, error: Error) {
+        self.errors.push(error);
+    }
+
+    pub fn into_errors(self) -> Vec<Error> {
+        self.errors
+    }
+
+    /// The index of the next token to be consumed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The span from the token at `start` to the end of the last consumed token.
+    pub fn span_from(&self, start: usize) -> Span {
+        let first = self.tokens.get(start);
+        let last = self.index.checked_sub(1).and_then(|i| self.tokens.get(i));
+
+        match (first, last) {
+            (Some(first), Some(last)) => first.span.to(last.span),
+            _ => Span::default(),
+        }
+    }
+
+    /// The span of the next token, or an empty span just after the last token if there are none left.
+    pub fn next_span(&self) -> Span {
+        match self.peek() {
+            Some(token) => token.span,
+            None => self.tokens.last().map_or(Span::default(), |last| {
+                Span::new(last.span.end, last.span.end)
+            }),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.tokens.len()
+    }
+
+    pub fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.index)
+    }
+
+    /// Looks `n` tokens past the next one, without consuming anything.
+    pub fn peek_nth(&self, n: usize) -> Option<&'a Token> {
+        self.tokens.get(self.index + n)
+    }
+
+    /// Whether the next token is of a certain kind.
+    pub fn peek_is(&self, kind: ShallowTokenKind) -> bool {
+        self.peek()
+            .map(|token| token.kind.as_shallow() == kind)
+            .unwrap_or(false)
+    }
+
+    /// Moves the cursor back to a token it has already passed.
+    pub fn rewind(&mut self, index: usize) {
+        self.index = index.min(self.index);
+    }
+
+    pub fn advance(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.index)?;
+        self.index += 1;
+        Some(token)
+    }
+
+    /// Consumes the next token only if it is of a certain kind.
+    ///
+    /// Returns whether the token was consumed.
+    pub fn eat(&mut self, kind: ShallowTokenKind) -> bool {
+        if self.peek_is(kind) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the next token, returning an error if it is not of a certain kind.
+    pub fn expect(&mut self, kind: ShallowTokenKind) -> Result<&'a TokenKind, Error> {
+        match self.peek() {
+            Some(token) if token.kind.as_shallow() == kind => {
+                self.index += 1;
+                Ok(&token.kind)
+            }
+            received => Err(Error::expected_token(
+                self.next_span(),
+                kind,
+                received.cloned(),
+            )),
+        }
+    }
+
+    pub fn expect_ident(&mut self) -> Result<String, Error> {
+        self.expect(ShallowTokenKind::Ident)
+            .map(|kind| kind.as_ident().unwrap().clone())
+    }
+}
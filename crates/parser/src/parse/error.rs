@@ -1,160 +1,200 @@
 use std::fmt::{Display, Formatter};
 
+use ast::{LineCol, Span};
+use diagnostics::{Code, Diagnostic, Label};
 use is_macro::Is;
 
 use crate::lex::{ShallowTokenKind, Token};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Error {
-    // TODO: Make this a span
-    pub index: usize,
+    /// The token the problem was found at, or an empty span after the last token if they ran out.
+    pub span: Span,
+    /// Where [`Self::span`] starts, if the source code was available to work it out.
+    pub line_col: Option<LineCol>,
     pub kind: ErrorKind,
-    // Whether the error can be recovered from.
-    //
-    // For example, a parser of a while loop can return recoverable if the `while` token isn't found,
-    // but not if the boolean expression associated with it is malformed.
-    //
-    // Additionally, if this is `false` the error should always be pushed to the top.
-    pub is_recoverable: bool,
 }
 
 impl std::error::Error for Error {}
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+        write!(f, "{}", self.kind)?;
+
+        match self.line_col {
+            Some(line_col) => write!(f, " at {line_col}."),
+            None => write!(f, " at bytes {}..{}.", self.span.start, self.span.end),
+        }
     }
 }
 
-#[derive(Debug, Is)]
+#[derive(Debug, Clone, Is)]
 pub enum ErrorKind {
     ExpectedToken {
         expected: ShallowTokenKind,
         received: Option<Token>,
     },
-    ExpectedBinaryOperator {
-        received: Option<Token>,
-    },
-    ExpectedAssignmentOperator {
-        received: Option<Token>,
-    },
     ExpectedLiteral {
         received: Option<Token>,
     },
     FailedToConsume,
-    NoValidExpr,
-    NoTokensProvided,
 }
 
 impl Error {
-    /// Adjusts [`Self::index`] by an index.
-    pub fn offset(mut self, by: usize) -> Self {
-        self.index += by;
-        self
+    pub fn expected_token(span: Span, expected: ShallowTokenKind, received: Option<Token>) -> Self {
+        Self::new(span, ErrorKind::ExpectedToken { expected, received })
     }
 
-    /// Sets the consumed [`Self::is_recoverable`] to `false`
-    pub fn unrecoverable(mut self) -> Self {
-        self.is_recoverable = false;
-        self
-    }
-
-    pub fn expected_token(
-        at_index: usize,
-        expected: ShallowTokenKind,
-        received: Option<Token>,
-    ) -> Self {
-        Self {
-            index: at_index,
-            kind: ErrorKind::ExpectedToken { expected, received },
-            is_recoverable: true,
-        }
+    pub fn expected_literal(span: Span, received: Option<Token>) -> Self {
+        Self::new(span, ErrorKind::ExpectedLiteral { received })
     }
 
-    pub fn expected_binary_operator(at_index: usize, received: Option<Token>) -> Self {
-        Self {
-            index: at_index,
-            kind: ErrorKind::ExpectedBinaryOperator { received },
-            is_recoverable: true,
-        }
+    pub fn failed_to_consume(span: Span) -> Self {
+        Self::new(span, ErrorKind::FailedToConsume)
     }
 
-    pub fn expected_assignment_operator(at_index: usize, received: Option<Token>) -> Self {
+    fn new(span: Span, kind: ErrorKind) -> Self {
         Self {
-            index: at_index,
-            kind: ErrorKind::ExpectedAssignmentOperator { received },
-            is_recoverable: true,
+            span,
+            line_col: None,
+            kind,
         }
     }
 
-    pub fn expected_literal(at_index: usize, received: Option<Token>) -> Self {
+    /// Works out [`Self::line_col`] from the source code the tokens were lexed from.
+    pub fn with_source(self, source: &str) -> Self {
         Self {
-            index: at_index,
-            kind: ErrorKind::ExpectedLiteral { received },
-            is_recoverable: true,
+            line_col: Some(LineCol::from_index(source, self.span.start)),
+            ..self
         }
     }
 
-    pub fn failed_to_consume(at_index: usize) -> Self {
-        Self {
-            index: at_index,
-            kind: ErrorKind::FailedToConsume,
-
-            is_recoverable: true,
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let label = match &self.kind {
+            ErrorKind::ExpectedToken { expected, .. } => {
+                format!("expected {}", expected.describe())
+            }
+            ErrorKind::ExpectedLiteral { .. } => "expected an expression".to_string(),
+            ErrorKind::FailedToConsume => "could not parse from here".to_string(),
+        };
+
+        let diagnostic = Diagnostic::error(self.kind.code(), self.kind.to_string())
+            .with_label(Label::primary(self.span, label));
+
+        match &self.kind {
+            ErrorKind::ExpectedToken {
+                expected: ShallowTokenKind::Semicolon,
+                ..
+            } => diagnostic.with_help("Statements end with a `;`."),
+            _ => diagnostic,
         }
     }
+}
 
-    pub fn no_valid_expr(at_index: usize) -> Self {
-        Self {
-            index: at_index,
-            kind: ErrorKind::NoValidExpr,
-            is_recoverable: true,
+impl ErrorKind {
+    pub fn code(&self) -> Code {
+        match self {
+            ErrorKind::ExpectedToken { .. } => Code::EXPECTED_TOKEN,
+            ErrorKind::ExpectedLiteral { .. } => Code::EXPECTED_EXPRESSION,
+            ErrorKind::FailedToConsume => Code::FAILED_TO_CONSUME,
         }
     }
+}
 
-    pub fn no_tokens_provided() -> Self {
-        Self {
-            index: 0,
-            kind: ErrorKind::NoTokensProvided,
-            is_recoverable: true,
-        }
+/// Describes what was found instead of what the parser expected.
+fn describe_received(received: &Option<Token>) -> String {
+    match received {
+        Some(token) => token.kind.describe(),
+        None => "the end of the input".to_string(),
     }
 }
 
 impl Display for ErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            ErrorKind::ExpectedToken { expected, received } => {
-                write!(f, "Expected {expected} got ")?;
-                match received {
-                    Some(token) => write!(f, "{}", token.kind),
-                    None => write!(f, "to the end of the buffer."),
-                }
-            }
-            ErrorKind::ExpectedBinaryOperator { received } => {
-                write!(f, "Expected binary operator got ")?;
-                match received {
-                    Some(token) => write!(f, "{}", token.kind),
-                    None => write!(f, "to the end of the buffer."),
-                }
-            }
-            ErrorKind::ExpectedAssignmentOperator { received } => {
-                write!(f, "Expected assignment operator got ")?;
-                match received {
-                    Some(token) => write!(f, "{}", token.kind),
-                    None => write!(f, "to the end of the buffer."),
-                }
-            }
-            ErrorKind::ExpectedLiteral { received } => {
-                write!(f, "Expected literal got ")?;
-                match received {
-                    Some(token) => write!(f, "{}", token.kind),
-                    None => write!(f, "to the end of the buffer."),
-                }
-            }
-            ErrorKind::FailedToConsume => write!(f, "Failed to consume the provided input."),
-            ErrorKind::NoValidExpr => write!(f, "No valid expression was found."),
-            ErrorKind::NoTokensProvided => write!(f, "No tokens were provided."),
+            ErrorKind::ExpectedToken { expected, received } => write!(
+                f,
+                "Expected {}, but found {}",
+                expected.describe(),
+                describe_received(received)
+            ),
+            ErrorKind::ExpectedLiteral { received } => write!(
+                f,
+                "Expected an expression, but found {}",
+                describe_received(received)
+            ),
+            ErrorKind::FailedToConsume => write!(f, "Could not parse all of the input"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::Span;
+    use diagnostics::{Code, LabelStyle};
+
+    use crate::{lex_string, parse_string, parse_tokens};
+
+    fn parse_err(source: &str) -> String {
+        match parse_string(source) {
+            Err(crate::Error::Parse(err)) => err.to_string(),
+            other => panic!("Expected a parse error, got {other:?}"),
         }
     }
+
+    #[test]
+    fn describes_expected_and_received_tokens() {
+        assert_eq!(
+            parse_err("let a = 1\nlet b = 2;"),
+            "Expected `;`, but found `let` at line 2, column 1."
+        );
+    }
+
+    #[test]
+    fn describes_end_of_input() {
+        assert_eq!(
+            parse_err("let x = [1, 2"),
+            "Expected `,`, but found the end of the input at line 1, column 14."
+        );
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        assert_eq!(
+            parse_err("let café = 1 +;"),
+            "Expected an expression, but found `;` at line 1, column 15."
+        );
+    }
+
+    #[test]
+    fn falls_back_to_bytes_without_source() {
+        let tokens = lex_string("let café = 1 +;").unwrap();
+        let err = parse_tokens(&tokens).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected an expression, but found `;` at bytes 15..16."
+        );
+    }
+
+    #[test]
+    fn becomes_a_diagnostic() {
+        let err = parse_string("let a = 1 2;").unwrap_err();
+        let diagnostic = err.to_diagnostic();
+
+        assert_eq!(diagnostic.code, Code::EXPECTED_TOKEN);
+        assert_eq!(diagnostic.message, "Expected `;`, but found `2`");
+        assert_eq!(diagnostic.labels[0].span, Span::new(10, 11));
+        assert_eq!(diagnostic.labels[0].style, LabelStyle::Primary);
+        assert_eq!(diagnostic.labels[0].message, "expected `;`");
+        assert!(diagnostic.help.is_some());
+    }
+
+    #[test]
+    fn lex_errors_become_diagnostics() {
+        let diagnostic = parse_string("let s = \"abc").unwrap_err().to_diagnostic();
+
+        assert_eq!(diagnostic.code, Code::UNTERMINATED_STRING);
+        assert_eq!(diagnostic.primary_span(), Some(Span::new(8, 8)));
+    }
 }
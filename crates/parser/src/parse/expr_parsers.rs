@@ -1,248 +1,609 @@
 use std::collections::HashMap;
 
-use ast::{BinaryOp, Expr, FnCall, Member};
-
-use super::common_parsers::parse_expr_list;
-use super::tokens_ext::{LocatedBinaryOp, TokensExt};
+use ast::{
+    Associativity, BinaryOp, BinaryOpKind, BlockExit, Expr, ExprKind, FnCall, Function, Member,
+    MethodCall, Range, Stmt, StmtKind, UnaryOp,
+};
+
+use super::common_parsers::{parse_expr_list, parse_prop_ident_list};
+use super::cursor::Cursor;
+use super::stmt_parsers::parse_body;
 use super::Error;
-use crate::lex::{ShallowTokenKind, Token, TokenKind};
-
-/// Runs all parsers over supplied source, returning the first success or last failure
-pub fn parse_expr(tokens: &[Token]) -> Result<Expr, Error> {
-    let parsers = [
-        parse_binary_op,
-        parse_fn_call,
-        parse_member,
-        parse_single_token,
-        parse_array_literal,
-        parse_object_literal,
-    ];
-
-    let mut last_failure = None;
-
-    for parser in parsers {
-        match parser(tokens) {
-            Ok(fe) => return Ok(fe),
-            Err(err) => {
-                if !err.is_recoverable {
-                    return Err(err);
-                } else {
-                    last_failure = Some(err);
-                }
-            }
-        }
+use crate::lex::{ShallowTokenKind, Template, TemplatePart, TokenKind};
+
+pub fn parse_expr(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let start = parse_binary_op(cursor, 0)?;
+
+    // Ranges bind looser than any binary operator, so `0..n + 1` ends at `n + 1`.
+    if !cursor.eat(ShallowTokenKind::DoubleDot) {
+        return Ok(start);
     }
 
-    Err(last_failure.unwrap())
+    let end = parse_binary_op(cursor, 0)?;
+    let span = start.span.to(end.span);
+
+    Ok(Expr::new(
+        ExprKind::Range(Range {
+            start: Box::new(start),
+            end: Box::new(end),
+        }),
+        span,
+    ))
 }
 
-fn parse_single_token(tokens: &[Token]) -> Result<Expr, Error> {
-    let token = tokens
-        .get(0)
-        .ok_or_else(|| Error::expected_literal(0, None))?
-        .clone();
-
-    let expr = match token.kind {
-        TokenKind::Number(n) => Expr::NumberLiteral(n),
-        TokenKind::String(s) => Expr::StringLiteral(s),
-        TokenKind::Ident(i) => Expr::Ident(i),
-        TokenKind::True => Expr::BoolLiteral(true),
-        TokenKind::False => Expr::BoolLiteral(false),
-        _ => return Err(Error::expected_literal(0, Some(token))),
-    };
+/// Parses a chain of binary operations using precedence climbing.
+///
+/// Only operators with a [`ast::BinaryOpKind::precedence`] of at least `min_precedence` are consumed,
+/// the rest are left for the caller.
+fn parse_binary_op(cursor: &mut Cursor, min_precedence: u8) -> Result<Expr, Error> {
+    let mut a = parse_unary_op(cursor)?;
+
+    while let Some(kind) = cursor.peek().and_then(|token| token.kind.as_binary_op()) {
+        let precedence = kind.precedence();
+
+        if precedence < min_precedence {
+            break;
+        }
+
+        cursor.advance();
 
-    if tokens.len() != 1 {
-        return Err(Error::failed_to_consume(1));
+        let next_min_precedence = match kind.associativity() {
+            Associativity::Left => precedence + 1,
+            Associativity::Right => precedence,
+        };
+
+        let b = parse_binary_op(cursor, next_min_precedence)?;
+        let span = a.span.to(b.span);
+
+        a = Expr::new(
+            ExprKind::BinaryOp(BinaryOp {
+                kind,
+                a: Box::new(a),
+                b: Box::new(b),
+            }),
+            span,
+        );
     }
 
-    Ok(expr)
+    Ok(a)
 }
 
-fn parse_binary_op(tokens: &[Token]) -> Result<Expr, Error> {
-    // Iterate over binary ops
-    // Check if possible to do: parse_expr()
-    // If not, check next op
-    let mut scan_start = 0;
+/// Parses any number of prefix operators, like `-` or `!`.
+///
+/// These bind tighter than every binary operator except `**`, so `-2 ** 2` is `-(2 ** 2)`.
+fn parse_unary_op(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let Some(kind) = cursor.peek().and_then(|token| token.kind.as_unary_op()) else {
+        return parse_postfix(cursor);
+    };
+
+    let start = cursor.index();
+    cursor.advance();
 
-    let (a, b, kind) = loop {
-        let LocatedBinaryOp { op, location } = tokens.locate_first_binary_op(scan_start)?;
+    let operand = parse_binary_op(cursor, BinaryOpKind::Pow.precedence())?;
 
-        // a + b
-        let a_tokens = &tokens[..location];
-        let Ok(a) = parse_expr(a_tokens) else {
-            scan_start = location + 1;
-            continue;
-        };
+    Ok(Expr::new(
+        ExprKind::UnaryOp(UnaryOp {
+            kind,
+            operand: Box::new(operand),
+        }),
+        cursor.span_from(start),
+    ))
+}
 
-        let b_tokens = &tokens[location + 1..];
-        let Ok(b) = parse_expr(b_tokens) else {
-            scan_start = location + 1;
-            continue;
+/// Parses an expression followed by any number of function calls or member accesses.
+fn parse_postfix(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let start = cursor.index();
+    let mut expr = parse_primary(cursor)?;
+
+    loop {
+        let kind = if cursor.peek_is(ShallowTokenKind::LeftBracket) {
+            parse_member(cursor, expr)?
+        } else if cursor.peek_is(ShallowTokenKind::Dot) {
+            parse_dot_access(cursor, expr)?
+        } else if cursor.peek_is(ShallowTokenKind::LeftParen) {
+            parse_fn_call(cursor, expr)?
+        } else {
+            return Ok(expr);
         };
 
-        let consumed_token_count = a_tokens.len() + b_tokens.len() + 1;
-        if consumed_token_count != tokens.len() {
-            return Err(Error::failed_to_consume(consumed_token_count));
-        }
+        expr = Expr::new(kind, cursor.span_from(start));
+    }
+}
 
-        break (a, b, op);
+fn parse_primary(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let Some(token) = cursor.peek() else {
+        return Err(Error::expected_literal(cursor.next_span(), None));
     };
 
-    Ok(Expr::BinaryOp(BinaryOp {
-        kind,
-        a: Box::new(a),
-        b: Box::new(b),
-    }))
+    let kind = match &token.kind {
+        TokenKind::Number(n) => ExprKind::NumberLiteral(*n),
+        TokenKind::String(s) => ExprKind::StringLiteral(s.clone()),
+        TokenKind::Template(template) => parse_template(template)?,
+        TokenKind::Ident(i) => ExprKind::Ident(i.clone()),
+        TokenKind::True => ExprKind::BoolLiteral(true),
+        TokenKind::False => ExprKind::BoolLiteral(false),
+        TokenKind::Null => ExprKind::NullLiteral,
+        TokenKind::LeftParen => return parse_paren_group(cursor),
+        TokenKind::LeftBracket => return parse_array_literal(cursor),
+        TokenKind::LeftBrace => return parse_object_literal(cursor),
+        TokenKind::Fn => return parse_function(cursor),
+        _ => {
+            return Err(Error::expected_literal(
+                cursor.next_span(),
+                Some(token.clone()),
+            ))
+        }
+    };
+
+    cursor.advance();
+
+    Ok(Expr::new(kind, token.span))
 }
 
-fn parse_fn_call(tokens: &[Token]) -> Result<Expr, Error> {
-    let identifier = tokens.get_token_kind(0, ShallowTokenKind::Ident)?;
+/// Parses each of the expressions embedded in a template string.
+fn parse_template(template: &Template) -> Result<ExprKind, Error> {
+    let mut parts = Vec::with_capacity(template.parts.len());
+
+    for part in &template.parts {
+        let part = match part {
+            TemplatePart::Literal(text) => ast::TemplatePart::Literal(text.clone()),
+            TemplatePart::Tokens(tokens) => {
+                let mut inner = Cursor::new(tokens);
+                let expr = parse_expr(&mut inner)?;
+
+                if !inner.is_finished() {
+                    return Err(Error::failed_to_consume(inner.next_span()));
+                }
+
+                ast::TemplatePart::Expr(expr)
+            }
+        };
+
+        parts.push(part);
+    }
 
-    let found_list = parse_expr_list(
-        &tokens[1..],
+    Ok(ExprKind::Template(parts))
+}
+
+/// Parses an expression wrapped in parentheses, like `(a + b)`
+///
+/// The parentheses are included in the span of the expression.
+fn parse_paren_group(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let start = cursor.index();
+
+    cursor.expect(ShallowTokenKind::LeftParen)?;
+    let expr = parse_expr(cursor)?;
+    cursor.expect(ShallowTokenKind::RightParen)?;
+
+    Ok(Expr::new(expr.kind, cursor.span_from(start)))
+}
+
+fn parse_fn_call(cursor: &mut Cursor, callee: Expr) -> Result<ExprKind, Error> {
+    let args = parse_expr_list(
+        cursor,
         ShallowTokenKind::Comma,
         ShallowTokenKind::LeftParen,
         ShallowTokenKind::RightParen,
-    )
-    .map_err(|err| err.offset(1))?;
-
-    if found_list.next_index + 1 != tokens.len() {
-        return Err(Error::failed_to_consume(found_list.next_index));
-    }
+    )?;
 
-    Ok(Expr::FnCall(FnCall {
-        ident: identifier.clone().ident().unwrap(),
-        args: found_list.iter_exprs().collect(),
+    Ok(ExprKind::FnCall(FnCall {
+        callee: Box::new(callee),
+        args,
     }))
 }
 
-fn parse_member(tokens: &[Token]) -> Result<Expr, Error> {
-    let open = tokens.locate_first(1, ShallowTokenKind::LeftBracket)?;
+/// Parses either `parent.child`, which is sugar for `parent["child"]`, or a method call `parent.child(args)`.
+fn parse_dot_access(cursor: &mut Cursor, parent: Expr) -> Result<ExprKind, Error> {
+    cursor.expect(ShallowTokenKind::Dot)?;
+
+    let ident_at = cursor.index();
+    let ident = cursor.expect_ident()?;
+
+    if !cursor.peek_is(ShallowTokenKind::LeftParen) {
+        return Ok(ExprKind::Member(Member {
+            parent: Box::new(parent),
+            child: Box::new(Expr::new(
+                ExprKind::StringLiteral(ident),
+                cursor.span_from(ident_at),
+            )),
+        }));
+    }
 
-    let close = tokens[open..]
-        .locate_last_matched_right(
-            ShallowTokenKind::LeftBracket,
-            ShallowTokenKind::RightBracket,
-        )
-        .map_err(|err| err.offset(open))?
-        + open;
+    let args = parse_expr_list(
+        cursor,
+        ShallowTokenKind::Comma,
+        ShallowTokenKind::LeftParen,
+        ShallowTokenKind::RightParen,
+    )?;
 
-    if close < tokens.len() - 1 {
-        return Err(Error::failed_to_consume(close));
-    }
+    Ok(ExprKind::MethodCall(MethodCall {
+        receiver: Box::new(parent),
+        method: ident,
+        args,
+    }))
+}
 
-    let child = parse_expr(&tokens[open + 1..close]).map_err(|err| err.offset(open + 1))?;
-    let parent = parse_expr(&tokens[0..open])?;
+fn parse_member(cursor: &mut Cursor, parent: Expr) -> Result<ExprKind, Error> {
+    cursor.expect(ShallowTokenKind::LeftBracket)?;
+    let child = parse_expr(cursor)?;
+    cursor.expect(ShallowTokenKind::RightBracket)?;
 
-    Ok(Expr::Member(Member {
+    Ok(ExprKind::Member(Member {
         parent: Box::new(parent),
         child: Box::new(child),
     }))
 }
 
-fn parse_array_literal(tokens: &[Token]) -> Result<Expr, Error> {
-    let found_list = parse_expr_list(
-        tokens,
+fn parse_array_literal(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let start = cursor.index();
+
+    let items = parse_expr_list(
+        cursor,
         ShallowTokenKind::Comma,
         ShallowTokenKind::LeftBracket,
         ShallowTokenKind::RightBracket,
     )?;
 
-    if found_list.next_index != tokens.len() {
-        return Err(Error::failed_to_consume(found_list.next_index));
-    }
-
-    Ok(Expr::ArrayLiteral(found_list.iter_exprs().collect()))
+    Ok(Expr::new(
+        ExprKind::ArrayLiteral(items),
+        cursor.span_from(start),
+    ))
 }
 
-fn parse_object_literal(tokens: &[Token]) -> Result<Expr, Error> {
-    let closing_index = tokens
-        .locate_last_matched_right(ShallowTokenKind::LeftBrace, ShallowTokenKind::RightBrace)?;
-
-    if closing_index == 1 {
-        return Ok(Expr::ObjectLiteral(HashMap::new()));
-    }
-
-    if closing_index != tokens.len() - 1 {
-        return Err(Error::failed_to_consume(closing_index));
-    }
+fn parse_object_literal(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let start = cursor.index();
+    cursor.expect(ShallowTokenKind::LeftBrace)?;
 
-    let mut current_start = 1;
     let mut items = HashMap::new();
-    let mut d = 0;
 
-    while current_start < closing_index {
-        let ident = tokens.get_token_kind(current_start, ShallowTokenKind::Ident)?;
-        tokens.get_token_kind(current_start + 1, ShallowTokenKind::Colon)?;
-        let current = &tokens[current_start + 2..closing_index - d];
+    if !cursor.eat(ShallowTokenKind::RightBrace) {
+        loop {
+            let ident = cursor.expect_ident()?;
+            cursor.expect(ShallowTokenKind::Colon)?;
+            let expr = parse_expr(cursor)?;
 
-        if current.is_empty() {
-            return Err(Error::no_valid_expr(current_start + 3));
-        }
-
-        if d != 0
-            && tokens
-                .get_token_kind(closing_index - d, ShallowTokenKind::Comma)
-                .is_err()
-        {
-            d += 1;
-            continue;
-        }
+            items.insert(ident, expr);
 
-        match parse_expr(current) {
-            Ok(expr) => {
-                items.insert(ident.as_ident().unwrap().to_string(), expr);
-                current_start += current.len() + 3;
-                d = 0;
+            if cursor.eat(ShallowTokenKind::RightBrace) {
+                break;
             }
-            Err(_) => d += 1,
+
+            cursor.expect(ShallowTokenKind::Comma)?;
         }
     }
 
-    Ok(Expr::ObjectLiteral(items))
+    Ok(Expr::new(
+        ExprKind::ObjectLiteral(items),
+        cursor.span_from(start),
+    ))
+}
+
+/// Parses an anonymous function, which is a generator if `fn` is followed by `*`.
+///
+/// The body is either a block, or `=>` followed by a single expression that gets returned.
+fn parse_function(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let start = cursor.index();
+
+    cursor.expect(ShallowTokenKind::Fn)?;
+    let is_generator = cursor.eat(ShallowTokenKind::Asterisk);
+    let prop_idents = parse_prop_ident_list(cursor)?;
+
+    let body = if cursor.eat(ShallowTokenKind::FatArrow) {
+        let returned = parse_expr(cursor)?;
+        let span = returned.span;
+
+        vec![Stmt::new(
+            StmtKind::BlockExit(BlockExit::FnReturn(Some(returned))),
+            span,
+        )]
+    } else {
+        parse_body(cursor)?
+    };
+
+    Ok(Expr::new(
+        ExprKind::Function(Function {
+            prop_idents,
+            body,
+            is_generator,
+        }),
+        cursor.span_from(start),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_array_literal, parse_binary_op};
-    use crate::parse::expr_parsers::{parse_fn_call, parse_object_literal};
+    use ast::{BinaryOp, BinaryOpKind, BlockExit, Expr, ExprKind, Span, Stmt, StmtKind, UnaryOp};
+
+    use super::parse_expr;
+    use crate::parse::cursor::Cursor;
     use crate::test_utils::tokenize;
 
-    // TODO: ADD WAY MORE TESTS
+    fn parse(source: &str) -> ExprKind {
+        let tokens = tokenize(source);
+        let mut cursor = Cursor::new(&tokens);
+
+        let expr = parse_expr(&mut cursor).unwrap();
+        assert!(cursor.is_finished());
+
+        expr.kind
+    }
 
     #[test]
     fn parses_add() {
-        let tokens = tokenize("1 + 23 / 2");
+        assert!(parse("1 + 23 / 2").is_binary_op());
+    }
+
+    #[test]
+    fn respects_precedence() {
+        let ExprKind::BinaryOp(BinaryOp { kind, a, .. }) = parse("1 + 2 * 3 - 4") else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_subtract());
+
+        let ExprKind::BinaryOp(BinaryOp { kind, b, .. }) = a.kind else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_add());
+        assert!(matches!(
+            b.kind,
+            ExprKind::BinaryOp(BinaryOp {
+                kind: BinaryOpKind::Multiply,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn pow_is_right_associative() {
+        let ExprKind::BinaryOp(BinaryOp { kind, a, b }) = parse("2 ** 3 ** 2") else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_pow());
+        assert!(a.kind.is_number_literal());
+        assert!(b.kind.is_binary_op());
+    }
 
-        let res = parse_binary_op(&tokens);
+    #[test]
+    fn unary_ops_bind_looser_than_pow() {
+        let ExprKind::UnaryOp(UnaryOp { kind, operand }) = parse("-2 ** 2") else {
+            panic!("Expected a unary operation.");
+        };
+
+        assert!(kind.is_negate());
+        assert!(operand.kind.is_binary_op());
+    }
+
+    #[test]
+    fn unary_ops_bind_tighter_than_multiply() {
+        let ExprKind::BinaryOp(BinaryOp { kind, a, b }) = parse("-a * !b") else {
+            panic!("Expected a binary operation.");
+        };
 
-        res.unwrap();
+        assert!(kind.is_multiply());
+        assert!(a.kind.is_unary_op());
+        assert!(b.kind.is_unary_op());
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let ExprKind::BinaryOp(BinaryOp { kind, b, .. }) = parse("a || b && c == 1") else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_or());
+
+        let ExprKind::BinaryOp(BinaryOp { kind, b, .. }) = b.kind else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_and());
+        assert!(b.kind.is_binary_op());
+    }
+
+    #[test]
+    fn bitwise_ops_bind_between_comparison_and_shift() {
+        let ExprKind::BinaryOp(BinaryOp { kind, a, b }) = parse("a | b ^ c & d << 1 != e") else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_not_equals());
+        assert!(b.kind.is_ident());
+
+        let ExprKind::BinaryOp(BinaryOp { kind, b, .. }) = a.kind else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_bit_or());
+
+        let ExprKind::BinaryOp(BinaryOp { kind, b, .. }) = b.kind else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_bit_xor());
+
+        let ExprKind::BinaryOp(BinaryOp { kind, b, .. }) = b.kind else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_bit_and());
+        assert!(matches!(
+            b.kind,
+            ExprKind::BinaryOp(BinaryOp {
+                kind: BinaryOpKind::ShiftLeft,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn range_binds_looser_than_binary_ops() {
+        let ExprKind::Range(range) = parse("a - 1..b + 1") else {
+            panic!("Expected a range.");
+        };
+
+        assert!(range.start.kind.is_binary_op());
+        assert!(range.end.kind.is_binary_op());
+    }
+
+    #[test]
+    fn parses_paren_group() {
+        let ExprKind::BinaryOp(BinaryOp { kind, a, b }) = parse("(a + b) * 2") else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert!(kind.is_multiply());
+        assert!(a.kind.is_binary_op());
+        assert!(b.kind.is_number_literal());
+    }
+
+    #[test]
+    fn parses_template() {
+        let ExprKind::Template(parts) =
+            parse(r#""Hi ${user["name"]}, you have ${len(items) + 1} items""#)
+        else {
+            panic!("Expected a template.");
+        };
+
+        assert_eq!(parts.len(), 5);
+        assert!(parts[1].as_expr().unwrap().kind.is_member());
+        assert!(parts[3].as_expr().unwrap().kind.is_binary_op());
+    }
+
+    #[test]
+    fn reports_template_errors_inside_template() {
+        let tokens = tokenize(r#"a + "${1 +}""#);
+
+        let err = parse_expr(&mut Cursor::new(&tokens)).unwrap_err();
+
+        // Just after the `+`, where the missing operand should be
+        assert_eq!(err.span, Span::new(10, 10));
+        assert!(err.kind.is_expected_literal());
     }
 
     #[test]
     fn parses_fn_call() {
-        let tokens = tokenize("test(a + 12, b)");
+        assert!(parse("test(a + 12, b)").is_fn_call());
+    }
+
+    #[test]
+    fn parses_call_chain() {
+        let ExprKind::FnCall(outer) = parse("handlers[0](1)(2)") else {
+            panic!("Expected a function call.");
+        };
 
-        let res = parse_fn_call(&tokens);
+        let ExprKind::FnCall(inner) = outer.callee.kind else {
+            panic!("Expected a function call.");
+        };
 
-        res.unwrap();
+        assert!(inner.callee.kind.is_member());
     }
 
     #[test]
-    fn parses_array_literal() {
-        let tokens = tokenize("[a, b, \"test\", 23, [1, 2]]");
+    fn parses_dot_access() {
+        let ExprKind::Member(member) = parse("user.address.city") else {
+            panic!("Expected a member access.");
+        };
+
+        assert!(member.parent.kind.is_member());
+        assert!(matches!(member.child.kind, ExprKind::StringLiteral(ref s) if s == "city"));
+    }
 
-        let res = parse_array_literal(&tokens);
+    #[test]
+    fn parses_method_call() {
+        let ExprKind::MethodCall(call) = parse("\"abc\".len().foo(1, 2)") else {
+            panic!("Expected a method call.");
+        };
 
-        res.unwrap();
+        assert_eq!(call.method, "foo");
+        assert_eq!(call.args.len(), 2);
+        assert!(call.receiver.kind.is_method_call());
     }
 
     #[test]
-    fn parses_object_literal() {
-        let tokens = tokenize("{ a: 1, b: 2, arr: [1, 2], str: \"test string\" }");
+    fn parses_member_chain() {
+        let ExprKind::Member(member) = parse("a[0][\"key\"]") else {
+            panic!("Expected a member access.");
+        };
 
-        let res = parse_object_literal(&tokens);
+        assert!(member.parent.kind.is_member());
+    }
 
-        res.unwrap();
+    #[test]
+    fn parses_null_literal() {
+        assert!(parse("null").is_null_literal());
+    }
+
+    #[test]
+    fn parses_function() {
+        let ExprKind::Function(function) = parse("fn(a, b) { return a + b; }") else {
+            panic!("Expected a function.");
+        };
+
+        assert_eq!(function.prop_idents, vec!["a", "b"]);
+        assert_eq!(function.body.len(), 1);
+        assert!(!function.is_generator);
+    }
+
+    #[test]
+    fn parses_anonymous_generator() {
+        let ExprKind::Function(function) = parse("fn*(n) { yield n; }") else {
+            panic!("Expected a function.");
+        };
+
+        assert!(function.is_generator);
+        assert!(function.body[0].kind.is_yield_stmt());
+    }
+
+    #[test]
+    fn parses_arrow_function() {
+        let ExprKind::Function(function) = parse("fn(x) => x * 2") else {
+            panic!("Expected a function.");
+        };
+
+        assert!(matches!(
+            &function.body[..],
+            [Stmt {
+                kind: StmtKind::BlockExit(BlockExit::FnReturn(Some(Expr {
+                    kind: ExprKind::BinaryOp(_),
+                    ..
+                }))),
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn spans_cover_whole_expressions() {
+        let tokens = tokenize("-(a + 1) * user.name(2)");
+        let expr = parse_expr(&mut Cursor::new(&tokens)).unwrap();
+
+        assert_eq!(expr.span, Span::new(0, 23));
+
+        let ExprKind::BinaryOp(BinaryOp { a, b, .. }) = expr.kind else {
+            panic!("Expected a binary operation.");
+        };
+
+        assert_eq!(a.span, Span::new(0, 8));
+        assert_eq!(b.span, Span::new(11, 23));
+
+        let ExprKind::UnaryOp(UnaryOp { operand, .. }) = a.kind else {
+            panic!("Expected a unary operation.");
+        };
+
+        // Parentheses are part of the expression they wrap
+        assert_eq!(operand.span, Span::new(1, 8));
+    }
+
+    #[test]
+    fn parses_array_literal() {
+        assert!(parse("[a, b, \"test\", 23, [1, 2]]").is_array_literal());
+    }
+
+    #[test]
+    fn parses_object_literal() {
+        assert!(parse("{ a: 1, b: 2, arr: [1, 2], str: \"test string\" }").is_object_literal());
     }
 }
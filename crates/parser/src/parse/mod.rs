@@ -1,8 +1,8 @@
 mod common_parsers;
+mod cursor;
 mod error;
 mod expr_parsers;
 mod stmt_parsers;
-mod tokens_ext;
 
 pub use error::{Error, ErrorKind};
-pub use stmt_parsers::parse_stmt_list;
+pub use stmt_parsers::{parse_stmt_list, parse_stmt_list_recovering};
@@ -1,323 +1,491 @@
-use ast::{BlockExit, FnDecl, Stmt, VarAssign, VarDecl};
+use ast::{BlockExit, FnDecl, ForLoop, IfElse, Stmt, StmtKind, VarAssign, VarDecl, WhileLoop};
 
-use super::common_parsers::{parse_prop_ident_list, FoundPropIdentList};
+use super::common_parsers::parse_prop_ident_list;
+use super::cursor::Cursor;
 use super::expr_parsers::parse_expr;
-use super::tokens_ext::{LocatedAssignOp, TokensExt};
 use super::Error;
-use crate::lex::{ShallowTokenKind, Token};
+use crate::lex::{ShallowTokenKind, Token, TokenKind};
 
-#[derive(Debug, Clone)]
-pub struct FoundStmt {
-    pub stmt: Stmt,
-    pub next_index: usize,
+pub fn parse_stmt_list(tokens: &[Token]) -> Result<Vec<Stmt>, Error> {
+    parse_remaining_stmts(&mut Cursor::new(tokens))
 }
 
-pub fn parse_stmt_list(tokens: &[Token]) -> Result<Vec<Stmt>, Error> {
-    let mut stmts = Vec::new();
+/// Parses as many statements as possible, returning every error that was recovered from along the way.
+///
+/// Statements that fail to parse are replaced with [`StmtKind::Error`].
+pub fn parse_stmt_list_recovering(tokens: &[Token]) -> (Vec<Stmt>, Vec<Error>) {
+    let mut cursor = Cursor::recovering(tokens);
+    // Every error is recovered from, so this can't fail.
+    let stmts = parse_remaining_stmts(&mut cursor).unwrap_or_default();
 
-    let mut current_index = 0;
+    (stmts, cursor.into_errors())
+}
 
-    while current_index < tokens.len() {
-        let FoundStmt { stmt, next_index } =
-            parse_stmt(&tokens[current_index..]).map_err(|err| err.offset(current_index))?;
+fn parse_remaining_stmts(cursor: &mut Cursor) -> Result<Vec<Stmt>, Error> {
+    let mut stmts = Vec::new();
 
-        current_index += next_index;
-        stmts.push(stmt);
+    while !cursor.is_finished() {
+        stmts.push(parse_list_stmt(cursor)?);
     }
 
     Ok(stmts)
 }
 
-/// Runs all parsers over supplied source, returning the first success or last failure
-pub fn parse_stmt(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    let parsers = [
-        parse_var_decl,
-        parse_var_assign,
-        parse_fn_decl,
-        parse_while_loop,
-        parse_if_else,
-        parse_return,
-        parse_break_continue,
-        parse_expr_stmt,
-    ];
-
-    let mut last_failure = None;
-
-    for parser in parsers {
-        match parser(tokens) {
-            Ok(fe) => return Ok(fe),
-            Err(err) => {
-                if !err.is_recoverable {
-                    return Err(err);
-                } else {
-                    last_failure = Some(err);
+/// Parses a statement that is part of a list, like a program or the body of a block.
+///
+/// When the cursor is recovering, a statement that fails to parse is skipped and left as an error node.
+fn parse_list_stmt(cursor: &mut Cursor) -> Result<Stmt, Error> {
+    let start = cursor.index();
+
+    match parse_stmt(cursor) {
+        Err(err) if cursor.is_recovering() => {
+            cursor.report(err);
+            cursor.rewind(start);
+            skip_stmt(cursor);
+
+            Ok(Stmt::new(StmtKind::Error, cursor.span_from(start)))
+        }
+        result => result,
+    }
+}
+
+/// Skips past the statement starting at the cursor, so parsing can pick up again after it.
+///
+/// A statement ends after a `;`, or after the `}` closing a block it opened. A `}` closing the block
+/// around the statement is left for that block to consume.
+fn skip_stmt(cursor: &mut Cursor) {
+    let start = cursor.index();
+    let mut depth = 0_usize;
+
+    while let Some(token) = cursor.peek() {
+        match token.kind.as_shallow() {
+            ShallowTokenKind::Semicolon if depth == 0 => {
+                cursor.advance();
+                return;
+            }
+            ShallowTokenKind::LeftParen
+            | ShallowTokenKind::LeftBrace
+            | ShallowTokenKind::LeftBracket => depth += 1,
+            ShallowTokenKind::RightParen
+            | ShallowTokenKind::RightBrace
+            | ShallowTokenKind::RightBracket
+                if depth == 0 =>
+            {
+                break;
+            }
+            ShallowTokenKind::RightBrace if depth == 1 => {
+                cursor.advance();
+                depth = 0;
+
+                // The statement carries on into an `else` branch, or a `;` after a block inside an expression.
+                if cursor.peek_is(ShallowTokenKind::Else) {
+                    continue;
                 }
+
+                cursor.eat(ShallowTokenKind::Semicolon);
+                return;
             }
+            ShallowTokenKind::RightParen
+            | ShallowTokenKind::RightBrace
+            | ShallowTokenKind::RightBracket => depth -= 1,
+            _ => (),
         }
+
+        cursor.advance();
     }
 
-    Err(last_failure.unwrap())
+    // A stray closing bracket can't start a statement, so it is skipped by itself.
+    if cursor.index() == start {
+        cursor.advance();
+    }
 }
 
-fn parse_var_decl(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    tokens.get_token_kind(0, ShallowTokenKind::Let)?;
+/// Looks at the next token to decide which kind of statement to parse.
+pub fn parse_stmt(cursor: &mut Cursor) -> Result<Stmt, Error> {
+    let Some(token) = cursor.peek() else {
+        return Err(Error::expected_literal(cursor.next_span(), None));
+    };
 
-    let identifier = tokens
-        .get_token_kind(1, ShallowTokenKind::Ident)
-        .map_err(Error::unrecoverable)?;
+    let start = cursor.index();
+
+    let kind = match token.kind {
+        TokenKind::Let => parse_var_decl(cursor),
+        // `fn` without a name is an anonymous function, which is just an expression
+        TokenKind::Fn if is_fn_decl(cursor) => parse_fn_decl(cursor),
+        TokenKind::While => parse_while_loop(cursor),
+        TokenKind::For => parse_for_loop(cursor),
+        TokenKind::If => parse_if_else(cursor),
+        TokenKind::Return => parse_return(cursor),
+        TokenKind::Yield => parse_yield(cursor),
+        TokenKind::Break | TokenKind::Continue => parse_break_continue(cursor),
+        _ => parse_expr_stmt(cursor),
+    }?;
+
+    Ok(Stmt::new(kind, cursor.span_from(start)))
+}
 
-    tokens
-        .get_token_kind(2, ShallowTokenKind::Equals)
-        .map_err(Error::unrecoverable)?;
+fn parse_var_decl(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::Let)?;
+    let ident = cursor.expect_ident()?;
+    cursor.expect(ShallowTokenKind::Equals)?;
+    let initializer = parse_expr(cursor)?;
+    cursor.expect(ShallowTokenKind::Semicolon)?;
 
-    let semi_location = tokens
-        .locate_first(3, ShallowTokenKind::Semicolon)
-        .map_err(Error::unrecoverable)?;
+    Ok(StmtKind::VarDecl(VarDecl { ident, initializer }))
+}
 
-    let expr =
-        parse_expr(&tokens[3..semi_location]).map_err(|err| err.offset(3).unrecoverable())?;
+/// Whether the `fn` at the cursor is followed by a name, with a `*` in between for generators.
+fn is_fn_decl(cursor: &Cursor) -> bool {
+    let name_at = if cursor.peek_nth(1).is_some_and(|t| t.kind.is_asterisk()) {
+        2
+    } else {
+        1
+    };
 
-    Ok(FoundStmt {
-        stmt: Stmt::VarDecl(VarDecl {
-            ident: identifier.clone().ident().unwrap(),
-            initializer: expr,
-        }),
-        next_index: semi_location + 1,
-    })
+    cursor.peek_nth(name_at).is_some_and(|t| t.kind.is_ident())
 }
 
-fn parse_var_assign(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    let LocatedAssignOp { op, location } = tokens.locate_first_assign_op(1)?;
-
-    let semi_location = tokens.locate_first(0, ShallowTokenKind::Semicolon)?;
+fn parse_fn_decl(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::Fn)?;
+    let is_generator = cursor.eat(ShallowTokenKind::Asterisk);
+    let ident = cursor.expect_ident()?;
+    let prop_idents = parse_prop_ident_list(cursor)?;
+    let body = parse_body(cursor)?;
 
-    let to = parse_expr(&tokens[0..location])?;
+    Ok(StmtKind::FnDecl(FnDecl {
+        ident,
+        prop_idents,
+        body,
+        is_generator,
+    }))
+}
 
-    let value =
-        parse_expr(&tokens[location + 1..semi_location]).map_err(|err| err.offset(location + 1))?;
+fn parse_while_loop(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::While)?;
+    let condition = parse_condition(cursor)?;
+    let body = parse_body(cursor)?;
 
-    Ok(FoundStmt {
-        stmt: Stmt::VarAssign(VarAssign { to, value, op }),
-        next_index: semi_location + 1,
-    })
+    Ok(StmtKind::WhileLoop(WhileLoop { condition, body }))
 }
 
-fn parse_fn_decl(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    tokens.get_token_kind(0, ShallowTokenKind::Fn)?;
+fn parse_for_loop(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::For)?;
+    cursor.expect(ShallowTokenKind::LeftParen)?;
+
+    let first = cursor.expect_ident()?;
+    let second = if cursor.eat(ShallowTokenKind::Comma) {
+        Some(cursor.expect_ident()?)
+    } else {
+        None
+    };
 
-    let identifier = tokens
-        .get_token_kind(1, ShallowTokenKind::Ident)
-        .map_err(Error::unrecoverable)?;
+    cursor.expect(ShallowTokenKind::In)?;
+    let iterable = parse_expr(cursor)?;
+    cursor.expect(ShallowTokenKind::RightParen)?;
 
-    let FoundPropIdentList {
-        prop_idents,
-        next_index,
-    } = parse_prop_ident_list(&tokens[2..]).map_err(|err| err.offset(2).unrecoverable())?;
+    let body = parse_body(cursor)?;
 
-    let FoundBody {
+    Ok(StmtKind::ForLoop(ForLoop {
+        first,
+        second,
+        iterable,
         body,
-        next_index: after_body,
-    } = parse_body(&tokens[next_index + 2..])
-        .map_err(|err| err.offset(next_index + 2).unrecoverable())?;
-
-    Ok(FoundStmt {
-        stmt: Stmt::FnDecl(FnDecl {
-            ident: identifier.clone().ident().unwrap(),
-            prop_idents,
-            body,
-        }),
-        next_index: next_index + 2 + after_body,
-    })
+    }))
 }
 
-fn parse_while_loop(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    tokens.get_token_kind(0, ShallowTokenKind::While)?;
+fn parse_return(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::Return)?;
 
-    let closing_paren_index = tokens[1..]
-        .locate_last_matched_right(ShallowTokenKind::LeftParen, ShallowTokenKind::RightParen)
-        .map_err(|err| err.offset(1).unrecoverable())?
-        + 1;
-
-    let expr =
-        parse_expr(&tokens[2..closing_paren_index]).map_err(|err| err.offset(2).unrecoverable())?;
+    let expr = if cursor.eat(ShallowTokenKind::Semicolon) {
+        None
+    } else {
+        let expr = parse_expr(cursor)?;
+        cursor.expect(ShallowTokenKind::Semicolon)?;
+        Some(expr)
+    };
 
-    let FoundBody {
-        body,
-        next_index: after_body,
-    } = parse_body(&tokens[closing_paren_index + 1..])
-        .map_err(|err| err.offset(closing_paren_index + 1).unrecoverable())?;
-
-    Ok(FoundStmt {
-        stmt: Stmt::WhileLoop(ast::WhileLoop {
-            condition: expr,
-            body,
-        }),
-        next_index: closing_paren_index + 1 + after_body,
-    })
+    Ok(StmtKind::BlockExit(BlockExit::FnReturn(expr)))
 }
 
-fn parse_return(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    tokens.get_token_kind(0, ShallowTokenKind::Return)?;
+fn parse_yield(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::Yield)?;
+    let expr = parse_expr(cursor)?;
+    cursor.expect(ShallowTokenKind::Semicolon)?;
 
-    let final_semi = tokens
-        .locate_first(0, ShallowTokenKind::Semicolon)
-        .map_err(Error::unrecoverable)?;
+    Ok(StmtKind::Yield(expr))
+}
 
-    let expr = if final_semi == 1 {
-        None
+/// Parse either a `break` or a `continue`
+fn parse_break_continue(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    let exit = if cursor.eat(ShallowTokenKind::Break) {
+        BlockExit::Break
     } else {
-        Some(parse_expr(&tokens[1..final_semi]).map_err(|err| err.offset(1).unrecoverable())?)
+        cursor.expect(ShallowTokenKind::Continue)?;
+        BlockExit::Continue
     };
 
-    Ok(FoundStmt {
-        stmt: Stmt::BlockExit(BlockExit::FnReturn(expr)),
-        next_index: final_semi + 1,
-    })
+    cursor.expect(ShallowTokenKind::Semicolon)?;
+
+    Ok(StmtKind::BlockExit(exit))
 }
 
-/// Parse either a `break` or a `continue`
-fn parse_break_continue(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    tokens.get_token_kind(1, ShallowTokenKind::Semicolon)?;
-
-    if let Ok(_found) = tokens.get_token_kind(0, ShallowTokenKind::Break) {
-        return Ok(FoundStmt {
-            stmt: Stmt::BlockExit(BlockExit::Break),
-            next_index: 2,
-        });
+fn parse_if_else(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    cursor.expect(ShallowTokenKind::If)?;
+    let condition = parse_condition(cursor)?;
+    let true_branch = parse_body(cursor)?;
+
+    let else_branch = if !cursor.eat(ShallowTokenKind::Else) {
+        Vec::new()
+    }
+    // Check if it is an `else if` statement
+    else if cursor.peek_is(ShallowTokenKind::If) {
+        vec![parse_stmt(cursor)?]
     }
+    // Otherwise it's just an `else` statement
+    else {
+        parse_body(cursor)?
+    };
 
-    tokens.get_token_kind(0, ShallowTokenKind::Continue)?;
-    Ok(FoundStmt {
-        stmt: Stmt::BlockExit(BlockExit::Continue),
-        next_index: 2,
-    })
+    Ok(StmtKind::IfElse(IfElse {
+        condition,
+        true_branch,
+        else_branch,
+    }))
 }
 
-fn parse_if_else(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    tokens.get_token_kind(0, ShallowTokenKind::If)?;
-
-    let closing_paren_index = tokens[1..]
-        .locate_last_matched_right(ShallowTokenKind::LeftParen, ShallowTokenKind::RightParen)
-        .map_err(|err| err.offset(1).unrecoverable())?
-        + 1;
-
-    let condition =
-        parse_expr(&tokens[2..closing_paren_index]).map_err(|err| err.offset(2).unrecoverable())?;
-
-    let FoundBody {
-        body: true_branch,
-        next_index: after_body,
-    } = parse_body(&tokens[closing_paren_index + 1..])
-        .map_err(|err| err.offset(closing_paren_index + 1).unrecoverable())?;
-
-    let after_body = after_body + closing_paren_index + 1;
-
-    if tokens
-        .get_token_kind(after_body, ShallowTokenKind::Else)
-        .is_ok()
-    {
-        // Check if it is an `else if` statement
-        if let Ok(FoundStmt {
-            stmt,
-            next_index: after_second_body,
-        }) = parse_if_else(&tokens[after_body + 1..])
-        {
-            Ok(FoundStmt {
-                stmt: Stmt::IfElse(ast::IfElse {
-                    condition,
-                    true_branch,
-                    else_branch: vec![stmt],
-                }),
-                next_index: after_body + 1 + after_second_body,
-            })
-        }
-        // Otherwise it's just an `else` statement
-        else {
-            let FoundBody {
-                body: else_branch,
-                next_index: after_second_body,
-            } = parse_body(&tokens[after_body + 1..])
-                .map_err(|err| err.offset(after_body + 1).unrecoverable())?;
-            Ok(FoundStmt {
-                stmt: Stmt::IfElse(ast::IfElse {
-                    condition,
-                    true_branch,
-                    else_branch,
-                }),
-                next_index: after_body + 1 + after_second_body,
+/// Parses either a plain expression statement or an assignment, since both start with an expression.
+fn parse_expr_stmt(cursor: &mut Cursor) -> Result<StmtKind, Error> {
+    let expr = parse_expr(cursor)?;
+
+    let stmt = match cursor.peek().and_then(|token| token.kind.as_assign_op()) {
+        Some(op) => {
+            cursor.advance();
+            let value = parse_expr(cursor)?;
+
+            StmtKind::VarAssign(VarAssign {
+                to: expr,
+                value,
+                op,
             })
         }
-    } else {
-        Ok(FoundStmt {
-            stmt: Stmt::IfElse(ast::IfElse {
-                condition,
-                true_branch,
-                else_branch: Vec::new(),
-            }),
-            next_index: after_body,
-        })
-    }
-}
-
-fn parse_expr_stmt(tokens: &[Token]) -> Result<FoundStmt, Error> {
-    let final_semi = tokens.locate_first(0, ShallowTokenKind::Semicolon)?;
+        None => StmtKind::Expr(expr),
+    };
 
-    let expr = parse_expr(&tokens[..final_semi]).map_err(Error::unrecoverable)?;
+    cursor.expect(ShallowTokenKind::Semicolon)?;
 
-    Ok(FoundStmt {
-        stmt: Stmt::Expr(expr),
-        next_index: final_semi + 1,
-    })
+    Ok(stmt)
 }
 
-struct FoundBody {
-    body: Vec<Stmt>,
-    next_index: usize,
+/// Parses the parenthesized condition of a `while` or `if`.
+fn parse_condition(cursor: &mut Cursor) -> Result<ast::Expr, Error> {
+    cursor.expect(ShallowTokenKind::LeftParen)?;
+    let condition = parse_expr(cursor)?;
+    cursor.expect(ShallowTokenKind::RightParen)?;
+
+    Ok(condition)
 }
 
-fn parse_body(tokens: &[Token]) -> Result<FoundBody, Error> {
-    let closing_brace_index = tokens
-        .locate_last_matched_right(ShallowTokenKind::LeftBrace, ShallowTokenKind::RightBrace)?;
+pub fn parse_body(cursor: &mut Cursor) -> Result<Vec<Stmt>, Error> {
+    cursor.expect(ShallowTokenKind::LeftBrace)?;
 
-    let body = parse_stmt_list(&tokens[1..closing_brace_index])
-        .map_err(|err| err.offset(1).unrecoverable())?;
+    let mut body = Vec::new();
 
-    Ok(FoundBody {
-        body,
-        next_index: closing_brace_index + 1,
-    })
+    while !cursor.eat(ShallowTokenKind::RightBrace) {
+        if cursor.is_finished() {
+            return Err(Error::expected_token(
+                cursor.next_span(),
+                ShallowTokenKind::RightBrace,
+                None,
+            ));
+        }
+
+        body.push(parse_list_stmt(cursor)?);
+    }
+
+    Ok(body)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_fn_decl, parse_if_else};
-    use crate::parse::stmt_parsers::parse_while_loop;
+    use ast::{AssignOpKind, BinaryOpKind, Span, StmtKind};
+
+    use super::{parse_stmt, parse_stmt_list, parse_stmt_list_recovering};
+    use crate::parse::cursor::Cursor;
     use crate::test_utils::tokenize;
 
+    fn parse(source: &str) -> StmtKind {
+        let tokens = tokenize(source);
+        let mut cursor = Cursor::new(&tokens);
+
+        let stmt = parse_stmt(&mut cursor).unwrap();
+        assert!(cursor.is_finished());
+
+        stmt.kind
+    }
+
     #[test]
     fn parses_fn_decl() {
-        let tokens = tokenize("fn main(a, b) { let cat = 2 + 3 / 2; }");
+        assert!(parse("fn main(a, b) { let cat = 2 + 3 / 2; }").is_fn_decl());
+    }
 
-        let res = parse_fn_decl(&tokens);
+    #[test]
+    fn parses_anonymous_fn_as_expr() {
+        assert!(parse("fn(a) { return a; };").is_expr());
+    }
 
-        res.unwrap();
+    #[test]
+    fn parses_generator_decl() {
+        let StmtKind::FnDecl(fn_decl) = parse("fn* count() { yield 1; yield 2; }") else {
+            panic!("Expected a function declaration.");
+        };
+
+        assert!(fn_decl.is_generator);
+        assert!(fn_decl.body.iter().all(|stmt| stmt.kind.is_yield_stmt()));
+    }
+
+    #[test]
+    fn parses_anonymous_generator_as_expr() {
+        assert!(parse("fn*() { yield 1; };").is_expr());
     }
 
     #[test]
     fn parses_while_loop() {
-        let tokens = tokenize("while (true){ test(); }");
+        assert!(parse("while (true){ test(); }").is_while_loop());
+    }
 
-        let res = parse_while_loop(&tokens);
+    #[test]
+    fn parses_for_loop() {
+        let StmtKind::ForLoop(for_loop) = parse("for (key, value in obj) { test(); }") else {
+            panic!("Expected a for loop.");
+        };
+
+        assert_eq!(for_loop.first, "key");
+        assert_eq!(for_loop.second.as_deref(), Some("value"));
+        assert!(for_loop.iterable.kind.is_ident());
+    }
 
-        res.unwrap();
+    #[test]
+    fn parses_for_loop_over_range() {
+        let StmtKind::ForLoop(for_loop) = parse("for (i in 0..len(arr)) { }") else {
+            panic!("Expected a for loop.");
+        };
+
+        assert!(for_loop.second.is_none());
+        assert!(for_loop.iterable.kind.is_range());
     }
 
     #[test]
     fn parses_if() {
-        let tokens = tokenize("if (true){ test(); }");
+        assert!(parse("if (true){ test(); }").is_if_else());
+    }
+
+    #[test]
+    fn parses_else_if() {
+        let StmtKind::IfElse(if_else) = parse("if (a){ } else if (b) { } else { test(); }") else {
+            panic!("Expected an if statement.");
+        };
+
+        assert!(if_else.else_branch[0].kind.is_if_else());
+    }
+
+    #[test]
+    fn parses_var_assign() {
+        assert!(parse("arr[2] += 20;").is_var_assign());
+    }
+
+    #[test]
+    fn parses_compound_assign() {
+        let StmtKind::VarAssign(assign) = parse("a <<= 2;") else {
+            panic!("Expected an assignment.");
+        };
+
+        assert!(matches!(
+            assign.op,
+            AssignOpKind::Op(BinaryOpKind::ShiftLeft)
+        ));
+    }
+
+    #[test]
+    fn spans_cover_whole_statements() {
+        let tokens = tokenize("let a = 1;\nif (a) {\n  a += 1;\n}");
+
+        let stmts = parse_stmt_list(&tokens).unwrap();
+        assert_eq!(stmts[0].span, Span::new(0, 10));
+        assert_eq!(stmts[1].span, Span::new(11, 31));
+
+        let StmtKind::IfElse(if_else) = &stmts[1].kind else {
+            panic!("Expected an if statement.");
+        };
+
+        assert_eq!(if_else.true_branch[0].span, Span::new(22, 29));
+    }
+
+    #[test]
+    fn reports_missing_semicolon() {
+        let tokens = tokenize("let a = 1 let b = 2;");
+
+        let err = parse_stmt_list(&tokens).unwrap_err();
+
+        // The second `let`
+        assert_eq!(err.span, Span::new(10, 13));
+    }
+
+    #[test]
+    fn recovers_at_semicolons_and_braces() {
+        let source =
+            "let a = ;\nfn f() {\n  let b = 1 2;\n  return b;\n}\nlet c = {x: };\nlet d = 4;";
+        let tokens = tokenize(source);
+
+        let (stmts, errors) = parse_stmt_list_recovering(&tokens);
+        assert_eq!(errors.len(), 3);
+
+        let kinds: Vec<_> = stmts.iter().map(|stmt| &stmt.kind).collect();
+        assert!(matches!(
+            kinds[..],
+            [
+                StmtKind::Error,
+                StmtKind::FnDecl(_),
+                StmtKind::Error,
+                StmtKind::VarDecl(_)
+            ]
+        ));
+
+        assert_eq!(&source[stmts[0].span.start..stmts[0].span.end], "let a = ;");
+        assert_eq!(
+            &source[stmts[2].span.start..stmts[2].span.end],
+            "let c = {x: };"
+        );
+
+        let StmtKind::FnDecl(fn_decl) = &stmts[1].kind else {
+            unreachable!()
+        };
+
+        assert!(fn_decl.body[0].kind.is_error());
+        assert!(fn_decl.body[1].kind.is_block_exit());
+    }
+
+    #[test]
+    fn recovery_skips_whole_blocks() {
+        let tokens = tokenize("if (a +) { b; } else { c; } d; ) e;");
+
+        let (stmts, errors) = parse_stmt_list_recovering(&tokens);
+
+        // The `if` and the stray `)`
+        assert_eq!(errors.len(), 2);
+        assert_eq!(stmts.len(), 4);
+        assert!(stmts[0].kind.is_error());
+        assert!(stmts[1].kind.is_expr());
+        assert!(stmts[2].kind.is_error());
+        assert!(stmts[3].kind.is_expr());
+    }
+
+    #[test]
+    fn recovery_matches_strict_parsing_without_errors() {
+        let tokens = tokenize("let a = 1; while (a < 3) { a += 1; }");
 
-        let res = parse_if_else(&tokens);
+        let (stmts, errors) = parse_stmt_list_recovering(&tokens);
 
-        res.unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(stmts.len(), parse_stmt_list(&tokens).unwrap().len());
     }
 }
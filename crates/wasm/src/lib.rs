@@ -1,8 +1,9 @@
+// Derives `Trace` and `Finalize`, whose impls trip `non_local_definitions`.
+#[allow(non_local_definitions)]
 mod println;
 
-use std::rc::Rc;
-
-use interpreter::{GcCell, Value};
+use diagnostics::{JsonRenderer, Renderer, TerminalRenderer};
+use interpreter::Value;
 use js_sys::Function;
 use println::PrintLn;
 use wasm_bindgen::{prelude::wasm_bindgen, throw_str};
@@ -19,19 +20,21 @@ impl Context {
         let mut inner = interpreter::Context::new();
         inner.add_stdlib();
 
-        inner.add_callable("println", Rc::new(GcCell::new(PrintLn::new(log_fn))));
+        inner.add_callable("println", PrintLn::new(log_fn));
 
         Self { inner }
     }
 
-    pub fn easy_eval(&mut self, program: &str) -> String {
-        let program = match parser::parse_string(program) {
+    pub fn easy_eval(&mut self, source: &str) -> String {
+        let renderer = TerminalRenderer::plain();
+
+        let program = match parser::parse_string(source) {
             Ok(program) => program,
-            Err(err) => throw_str(&err.to_string()),
+            Err(err) => throw_str(&renderer.render(&[err.to_diagnostic()], source)),
         };
 
-        match self.inner.eval_program(&program) {
-            Err(err) => throw_str(&err.to_string()),
+        match self.inner.run_program(&program) {
+            Err(err) => throw_str(&renderer.render(&[err.to_diagnostic()], source)),
             Ok(returned) => format!(
                 "{}",
                 returned
@@ -42,3 +45,14 @@ impl Context {
         }
     }
 }
+
+/// Finds every syntax error in the source without running it, as a JSON array of diagnostics.
+#[wasm_bindgen]
+pub fn check(source: &str) -> String {
+    let diagnostics = match parser::parse_string_recovering(source) {
+        Ok((_, errors)) => errors.iter().map(|err| err.to_diagnostic()).collect(),
+        Err(err) => vec![err.to_diagnostic()],
+    };
+
+    JsonRenderer.render(&diagnostics, source)
+}